@@ -0,0 +1,41 @@
+use std::convert::TryFrom;
+
+use cid::Cid;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const CID_V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+const CID_V1: &str = "bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4";
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("v0 str", |b| b.iter(|| Cid::try_from(black_box(CID_V0))));
+    group.bench_function("v0 string", |b| {
+        b.iter(|| Cid::try_from(black_box(CID_V0.to_string())))
+    });
+    group.bench_function("v1 str", |b| b.iter(|| Cid::try_from(black_box(CID_V1))));
+    group.bench_function("v1 string", |b| {
+        b.iter(|| Cid::try_from(black_box(CID_V1.to_string())))
+    });
+
+    let bytes_v0 = Cid::try_from(CID_V0).unwrap().to_bytes();
+    let bytes_v1 = Cid::try_from(CID_V1).unwrap().to_bytes();
+    group.bench_function("v0 bytes", |b| {
+        b.iter(|| Cid::try_from(black_box(bytes_v0.as_slice())))
+    });
+    group.bench_function("v1 bytes", |b| {
+        b.iter(|| Cid::try_from(black_box(bytes_v1.as_slice())))
+    });
+    group.finish();
+}
+
+fn to_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_string");
+    let cid_v0 = Cid::try_from(CID_V0).unwrap();
+    let cid_v1 = Cid::try_from(CID_V1).unwrap();
+    group.bench_function("v0", |b| b.iter(|| black_box(cid_v0).to_string()));
+    group.bench_function("v1", |b| b.iter(|| black_box(cid_v1).to_string()));
+    group.finish();
+}
+
+criterion_group!(benches, parse, to_string);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! Node.js bindings, based on [napi-rs].
+//!
+//! The module exports a `Cid` class. The addon is built with the napi-rs CLI, `npm run build`.
+//!
+//! [napi-rs]: https://napi.rs
+use std::convert::TryFrom;
+
+use napi::bindgen_prelude::{BigInt, Buffer};
+use napi::Status;
+use napi_derive::napi;
+
+use cid::{Cid, Error};
+
+fn to_napi_error(error: Error) -> napi::Error {
+    napi::Error::new(Status::InvalidArg, error.to_string())
+}
+
+/// A CID.
+#[napi(js_name = "Cid")]
+pub struct JsCid {
+    inner: Cid,
+}
+
+#[napi]
+impl JsCid {
+    /// Parses the string representation of a CID.
+    #[napi(factory)]
+    pub fn parse(s: String) -> napi::Result<Self> {
+        Ok(Self {
+            inner: Cid::try_from(s).map_err(to_napi_error)?,
+        })
+    }
+
+    /// Parses the binary representation of a CID.
+    #[napi(factory)]
+    pub fn from_bytes(bytes: Buffer) -> napi::Result<Self> {
+        Ok(Self {
+            inner: Cid::try_from(bytes.as_ref()).map_err(to_napi_error)?,
+        })
+    }
+
+    /// The version.
+    #[napi(getter)]
+    pub fn version(&self) -> u32 {
+        u64::from(self.inner.version()) as u32
+    }
+
+    /// The codec.
+    #[napi(getter)]
+    pub fn codec(&self) -> BigInt {
+        self.inner.codec().into()
+    }
+
+    /// Returns the string representation.
+    #[napi(js_name = "toString")]
+    pub fn to_js_string(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// Returns the binary representation.
+    #[napi]
+    pub fn bytes(&self) -> Buffer {
+        self.inner.to_bytes().into()
+    }
+
+    /// Returns whether both CIDs are equal.
+    #[napi]
+    pub fn equals(&self, other: &JsCid) -> bool {
+        self.inner == other.inner
+    }
+}
@@ -0,0 +1,134 @@
+//! Derive macro for newtype wrappers around CIDs, re-exported by the `cid` crate with the `derive`
+//! feature.
+
+#![deny(missing_docs)]
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Member};
+
+/// Forwards the standard trait implementations of a CID newtype to the wrapped CID.
+///
+/// The struct needs to have exactly one field, of type `cid::Cid` or `cid::CidGeneric<S>`. It gets
+/// `Display`, `FromStr`, `TryFrom<&[u8]>`, `AsRef`, `Deref` and `From` in both directions, and the
+/// Serde implementations if the `serde` feature of `cid` is enabled.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, cid::CidWrapper)]
+/// struct BlockCid(cid::Cid);
+/// ```
+#[proc_macro_derive(CidWrapper)]
+pub fn derive_cid_wrapper(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "CidWrapper can only be derived for structs",
+            ))
+        }
+    };
+    if fields.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            input,
+            "CidWrapper requires a struct with exactly one field",
+        ));
+    }
+    let field = fields.iter().next().expect("There is one field");
+    let inner = &field.ty;
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(0.into()),
+    };
+    let wrap = match fields {
+        Fields::Named(_) => quote!(Self { #member: cid }),
+        _ => quote!(Self(cid)),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut de_generics = input.generics.clone();
+    de_generics.params.insert(0, parse_quote!('de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#inner> for #name #ty_generics #where_clause {
+            fn from(cid: #inner) -> Self {
+                #wrap
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for #inner #where_clause {
+            fn from(wrapper: #name #ty_generics) -> Self {
+                wrapper.#member
+            }
+        }
+
+        impl #impl_generics ::core::convert::AsRef<#inner> for #name #ty_generics #where_clause {
+            fn as_ref(&self) -> &#inner {
+                &self.#member
+            }
+        }
+
+        impl #impl_generics ::core::ops::Deref for #name #ty_generics #where_clause {
+            type Target = #inner;
+
+            fn deref(&self) -> &#inner {
+                &self.#member
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.#member, f)
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::cid::Error;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, ::cid::Error> {
+                let cid = <#inner as ::core::str::FromStr>::from_str(s)?;
+                ::core::result::Result::Ok(#wrap)
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&[u8]> for #name #ty_generics #where_clause {
+            type Error = ::cid::Error;
+
+            fn try_from(bytes: &[u8]) -> ::core::result::Result<Self, ::cid::Error> {
+                let cid = <#inner as ::core::convert::TryFrom<&[u8]>>::try_from(bytes)?;
+                ::core::result::Result::Ok(#wrap)
+            }
+        }
+
+        ::cid::__if_serde! {
+            impl #impl_generics ::cid::__private::serde::Serialize for #name #ty_generics #where_clause {
+                fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: ::cid::__private::serde::Serializer,
+                {
+                    ::cid::__private::serde::Serialize::serialize(&self.#member, serializer)
+                }
+            }
+
+            impl #de_impl_generics ::cid::__private::serde::Deserialize<'de> for #name #ty_generics #where_clause {
+                fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+                where
+                    __D: ::cid::__private::serde::Deserializer<'de>,
+                {
+                    let cid = <#inner as ::cid::__private::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                    ::core::result::Result::Ok(#wrap)
+                }
+            }
+        }
+    })
+}
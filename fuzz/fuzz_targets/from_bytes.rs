@@ -0,0 +1,14 @@
+#![no_main]
+
+use std::convert::TryFrom;
+
+use cid::Cid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(cid) = Cid::try_from(data) {
+        let bytes = cid.to_bytes();
+        assert_eq!(&data[..bytes.len()], &bytes[..]);
+        assert_eq!(Cid::try_from(bytes).unwrap(), cid);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use std::convert::TryFrom;
+
+use cid::Cid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Ok(cid) = Cid::try_from(data) {
+        assert_eq!(Cid::try_from(cid.to_string()).unwrap(), cid);
+    }
+});
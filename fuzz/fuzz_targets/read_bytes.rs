@@ -0,0 +1,37 @@
+#![no_main]
+
+use std::convert::TryFrom;
+use std::io::{self, Read};
+
+use cid::Cid;
+use libfuzzer_sys::fuzz_target;
+
+/// A reader returning the data in chunks of at most `chunk` bytes, like a socket would.
+struct Chunked<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.chunk).min(self.data.len());
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((&chunk, data)) = data.split_first() {
+        let reader = Chunked {
+            data,
+            chunk: usize::from(chunk).max(1),
+        };
+        // Reading in chunks must not make any difference.
+        match (Cid::read_bytes(reader), Cid::try_from(data)) {
+            (Ok(chunked), Ok(whole)) => assert_eq!(chunked, whole),
+            (Err(_), Err(_)) => {}
+            (chunked, whole) => panic!("{:?} != {:?}", chunked, whole),
+        }
+    }
+});
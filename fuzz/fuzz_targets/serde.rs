@@ -0,0 +1,11 @@
+#![no_main]
+
+use cid::Cid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(cid) = serde_json::from_slice::<Cid>(data) {
+        let json = serde_json::to_vec(&cid).unwrap();
+        assert_eq!(serde_json::from_slice::<Cid>(&json).unwrap(), cid);
+    }
+});
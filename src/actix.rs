@@ -0,0 +1,74 @@
+//! [actix-web] support.
+//!
+//! A [`Cid`] can be used directly as an extractor for a route with a single path parameter, e.g.
+//! `/ipfs/{cid}`. Invalid CIDs are rejected with `400 Bad Request` and the error message as body.
+//!
+//! Note that `web::Path<Cid>` and `web::Query<Cid>` don't work, as the Serde representation of CIDs
+//! is binary; parameters of such extractors need to be extracted as strings and parsed.
+//!
+//! [actix-web]: https://actix.rs
+use std::future::{ready, Ready};
+
+use actix_web::dev::Payload;
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::StatusCode;
+use actix_web::{FromRequest, HttpRequest, ResponseError};
+
+use crate::cid::Cid;
+use crate::error::Error;
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+impl<const S: usize> FromRequest for Cid<S> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let mut params = req.match_info().iter();
+        let cid = match (params.next(), params.next()) {
+            (Some((_, value)), None) => value.parse().map_err(Error::into),
+            _ => Err(ErrorInternalServerError(
+                "A CID extractor requires a route with exactly one path parameter",
+            )),
+        };
+        ready(cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+    use actix_web::FromRequest;
+    use futures_executor::block_on;
+
+    use crate::Cid;
+
+    #[test]
+    fn extract() {
+        let cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        let req = TestRequest::default().param("cid", cid).to_http_request();
+        assert_eq!(block_on(Cid::extract(&req)).unwrap().to_string(), cid);
+
+        let req = TestRequest::default()
+            .param("cid", "bafyinvalid")
+            .to_http_request();
+        let error = block_on(Cid::extract(&req)).unwrap_err();
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(error.to_string(), "Failed to parse multihash");
+
+        let req = TestRequest::default().to_http_request();
+        let error = block_on(Cid::extract(&req)).unwrap_err();
+        assert_eq!(
+            error.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
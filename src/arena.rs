@@ -0,0 +1,62 @@
+//! Bulk decoding of CIDs into a bump arena.
+//!
+//! Decoding millions of CIDs (e.g. a whole CAR index) results in millions of small allocations.
+//! The functions in this module allocate everything into a caller provided [`Bump`] arena
+//! instead, so that it can be freed at once.
+use core::fmt::Write;
+
+use bumpalo::collections::{String, Vec};
+use bumpalo::Bump;
+
+use crate::cid::Cid;
+use crate::cid_vec::CidRef;
+use crate::error::Result;
+
+/// Decodes a sequence of concatenated binary CIDs.
+///
+/// The digests are copied into the arena, the input can be dropped afterwards.
+pub fn decode_in<'bump>(bump: &'bump Bump, mut bytes: &[u8]) -> Result<Vec<'bump, CidRef<'bump>>> {
+    let mut cids = Vec::new_in(bump);
+    while !bytes.is_empty() {
+        let (cid, len) = CidRef::decode(bytes)?;
+        cids.push(cid.with_digest(bump.alloc_slice_copy(cid.digest())));
+        bytes = &bytes[len..];
+    }
+    Ok(cids)
+}
+
+/// Returns the string representation of a CID, allocated in the arena.
+pub fn to_str_in<'bump, const S: usize>(bump: &'bump Bump, cid: &Cid<S>) -> &'bump str {
+    let mut output = String::new_in(bump);
+    write!(output, "{}", cid).expect("writing to a string never fails");
+    output.into_bump_str()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use bumpalo::Bump;
+    use multihash::Multihash;
+
+    use super::{decode_in, to_str_in};
+    use crate::{Cid, CidRef};
+
+    #[test]
+    fn decode() {
+        let cids = [
+            Cid::new_v0(Multihash::wrap(0x12, &[1; 32]).unwrap()).unwrap(),
+            Cid::new_v1(0x55, Multihash::wrap(0x12, &[2; 32]).unwrap()),
+            Cid::new_v1(0x71, Multihash::wrap(0x00, &[3; 5]).unwrap()),
+        ];
+        let bytes: std::vec::Vec<u8> = cids.iter().flat_map(|cid| cid.to_bytes()).collect();
+
+        let bump = Bump::new();
+        let decoded = decode_in(&bump, &bytes).unwrap();
+        drop(bytes);
+        assert_eq!(decoded.len(), cids.len());
+        for (cid_ref, cid) in decoded.iter().zip(cids.iter()) {
+            assert_eq!(*cid_ref, CidRef::from(cid));
+        }
+
+        assert_eq!(to_str_in(&bump, &cids[0]), cids[0].to_string());
+    }
+}
@@ -0,0 +1,81 @@
+//! [axum] support.
+//!
+//! A [`Cid`] can be used directly as an extractor for a route with a single path parameter, e.g.
+//! `/ipfs/{cid}`. Invalid CIDs are rejected with `400 Bad Request` and the error message as body.
+//!
+//! Note that `Path<Cid>` and `Query<Cid>` don't work, as the Serde representation of CIDs is binary;
+//! parameters of such extractors need to be extracted as strings and parsed.
+//!
+//! [axum]: https://github.com/tokio-rs/axum
+use axum::extract::{FromRequestParts, RawPathParams};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+use crate::cid::Cid;
+use crate::error::Error;
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+impl<S, const SIZE: usize> FromRequestParts<S> for Cid<SIZE>
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let mut params = params.iter();
+        match (params.next(), params.next()) {
+            (Some((_, value)), None) => value.parse().map_err(Error::into_response),
+            _ => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "A CID extractor requires a route with exactly one path parameter",
+            )
+                .into_response()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use futures_executor::block_on;
+    use tower::ServiceExt;
+
+    use crate::Cid;
+
+    fn get_body(uri: &str) -> (StatusCode, String) {
+        let app = Router::new().route(
+            "/ipfs/{cid}",
+            get(|cid: Cid| async move { cid.to_string() }),
+        );
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = block_on(app.oneshot(request)).unwrap();
+        let status = response.status();
+        let body = block_on(to_bytes(response.into_body(), usize::MAX)).unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn extract() {
+        let cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        assert_eq!(
+            get_body(&format!("/ipfs/{}", cid)),
+            (StatusCode::OK, cid.into())
+        );
+        assert_eq!(
+            get_body("/ipfs/bafyinvalid"),
+            (StatusCode::BAD_REQUEST, "Failed to parse multihash".into())
+        );
+    }
+}
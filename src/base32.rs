@@ -0,0 +1,95 @@
+//! Base32 encoding streamed into a [`fmt::Write`].
+//!
+//! CIDv1 are base32 encoded by default. Encoding the bytes while they are written means neither
+//! the binary CID nor the string need to be allocated.
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use core2::io;
+
+/// The lowercase RFC 4648 base32 alphabet.
+const LOWER: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+/// The uppercase RFC 4648 base32 alphabet.
+const UPPER: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encodes the bytes written to it as unpadded base32.
+///
+/// [`Encoder::finish`] must be called to write the last character.
+pub(crate) struct Encoder<W> {
+    output: W,
+    alphabet: &'static [u8; 32],
+    /// The bits not encoded yet, there are always less than 5.
+    bits: u16,
+    len: u32,
+}
+
+impl<W: fmt::Write> Encoder<W> {
+    /// Creates an encoder using either the lowercase or the uppercase alphabet.
+    pub(crate) fn new(output: W, uppercase: bool) -> Self {
+        Self {
+            output,
+            alphabet: if uppercase { UPPER } else { LOWER },
+            bits: 0,
+            len: 0,
+        }
+    }
+
+    fn write_char(&mut self, value: u16) -> fmt::Result {
+        let c = self.alphabet[usize::from(value & 0x1f)];
+        self.output.write_char(char::from(c))
+    }
+
+    /// Writes the remaining bits, padded with zeros.
+    pub(crate) fn finish(mut self) -> fmt::Result {
+        if self.len > 0 {
+            self.write_char(self.bits << (5 - self.len))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> io::Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            self.bits = (self.bits << 8) | u16::from(byte);
+            self.len += 8;
+            while self.len >= 5 {
+                self.len -= 5;
+                self.write_char(self.bits >> self.len)
+                    .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
+            }
+            self.bits &= (1 << self.len) - 1;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use io::Write;
+    use multibase::Base;
+
+    #[test]
+    fn matches_multibase() {
+        let input: Vec<u8> = (0..=255u8).map(|i| i.wrapping_mul(37)).collect();
+        for len in 0..input.len() {
+            for (uppercase, base) in [(false, Base::Base32Lower), (true, Base::Base32Upper)] {
+                let mut output = String::new();
+                let mut encoder = Encoder::new(&mut output, uppercase);
+                // Written in two parts, to check that the bits are carried over.
+                encoder.write_all(&input[..len / 3]).unwrap();
+                encoder.write_all(&input[len / 3..len]).unwrap();
+                encoder.finish().unwrap();
+                assert_eq!(output, base.encode(&input[..len]));
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Base58btc encoding and decoding specialized for CIDv0.
+//!
+//! A CIDv0 is always a 34 bytes long SHA2-256 multihash, which is 46 characters long once
+//! encoded. Knowing the sizes upfront allows the conversion to work on fixed size buffers, instead
+//! of going through the generic multibase machinery.
+extern crate alloc;
+
+use alloc::string::String;
+use core::fmt;
+
+/// The length of a binary CIDv0.
+pub(crate) const V0_BINARY_LEN: usize = 34;
+/// The length of a base58btc encoded CIDv0.
+const V0_STR_LEN: usize = 46;
+
+/// The base58btc alphabet.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Maps an ASCII character to its base58btc value, invalid characters map to `0xff`.
+const DECODE_TABLE: [u8; 128] = {
+    let mut table = [0xff; 128];
+    let mut i = 0;
+    while i < ALPHABET.len() {
+        table[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Converts a binary CIDv0 into base58 digits, returns the digits in little-endian order, their
+/// number and the number of leading zero bytes.
+fn digits_v0(input: &[u8; V0_BINARY_LEN]) -> ([u8; V0_STR_LEN + 1], usize, usize) {
+    // One more digit than needed for a CIDv0, so that any 34 bytes input fits.
+    let mut digits = [0u8; V0_STR_LEN + 1];
+    let mut len = 0;
+    for &byte in input {
+        let mut carry = u32::from(byte);
+        for digit in digits[..len].iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+
+    let zeros = input.iter().take_while(|&&byte| byte == 0).count();
+    (digits, len, zeros)
+}
+
+/// Encodes a binary CIDv0 into a [`fmt::Write`].
+pub(crate) fn write_v0<W: fmt::Write>(input: &[u8; V0_BINARY_LEN], mut output: W) -> fmt::Result {
+    let (digits, len, zeros) = digits_v0(input);
+    for _ in 0..zeros {
+        output.write_char('1')?;
+    }
+    for &digit in digits[..len].iter().rev() {
+        output.write_char(char::from(ALPHABET[usize::from(digit)]))?;
+    }
+    Ok(())
+}
+
+/// Encodes a binary CIDv0.
+pub(crate) fn encode_v0(input: &[u8; V0_BINARY_LEN]) -> String {
+    let mut output = String::with_capacity(V0_STR_LEN);
+    write_v0(input, &mut output).expect("writing to a string never fails");
+    output
+}
+
+/// Decodes a base58btc encoded CIDv0.
+///
+/// Returns `None` if the input is not valid base58btc or doesn't decode into exactly 34 bytes.
+pub(crate) fn decode_v0(input: &str) -> Option<[u8; V0_BINARY_LEN]> {
+    if input.len() != V0_STR_LEN {
+        return None;
+    }
+
+    // The bytes in little-endian order.
+    let mut bytes = [0u8; V0_BINARY_LEN];
+    let mut len = 0;
+    for &c in input.as_bytes() {
+        let value = *DECODE_TABLE.get(usize::from(c))?;
+        if value == 0xff {
+            return None;
+        }
+        let mut carry = u32::from(value);
+        for byte in bytes[..len].iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            *bytes.get_mut(len)? = carry as u8;
+            len += 1;
+            carry >>= 8;
+        }
+    }
+
+    // Every leading `1` is a leading zero byte.
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    if zeros + len != V0_BINARY_LEN {
+        return None;
+    }
+    bytes.reverse();
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_v0, encode_v0, write_v0, V0_BINARY_LEN};
+    use multibase::Base;
+
+    #[test]
+    fn matches_multibase() {
+        let mut input = [0u8; V0_BINARY_LEN];
+        input[0] = 0x12;
+        input[1] = 0x20;
+        for seed in 0..=255u8 {
+            for (i, byte) in input[2..].iter_mut().enumerate() {
+                *byte = seed
+                    .wrapping_mul(31)
+                    .wrapping_add(i as u8)
+                    .rotate_left(u32::from(seed));
+            }
+            let encoded = encode_v0(&input);
+            assert_eq!(encoded, Base::Base58Btc.encode(input));
+            let mut written = String::new();
+            write_v0(&input, &mut written).unwrap();
+            assert_eq!(written, encoded);
+            assert_eq!(decode_v0(&encoded), Some(input));
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            decode_v0("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zIII"),
+            None
+        );
+        assert_eq!(
+            decode_v0("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1"),
+            None
+        );
+        // Decodes into less than 34 bytes.
+        assert_eq!(
+            decode_v0("2222222222222222222222222222222222222222222222"),
+            None
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Bitcoin block and transaction CIDs.
+//!
+//! Bitcoin blocks and transactions are identified by the double SHA2-256 hash of their
+//! serialization, as CIDs with the `dbl-sha2-256` multihash. Bitcoin displays those hashes with
+//! their bytes reversed, e.g. the genesis block hash
+//! `000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f` ends with the zero bytes of
+//! the proof of work. The functions here take and return that display form and store the digest
+//! in its natural byte order, as IPLD does.
+extern crate alloc;
+
+use alloc::string::String;
+use core::convert::TryInto;
+
+use multibase::Base;
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// The `bitcoin-block` multicodec.
+pub const BITCOIN_BLOCK: u64 = 0xb0;
+/// The `bitcoin-tx` multicodec.
+pub const BITCOIN_TX: u64 = 0xb1;
+/// The `bitcoin-witness-commitment` multicodec.
+pub const BITCOIN_WITNESS_COMMITMENT: u64 = 0xb2;
+/// The `dbl-sha2-256` multihash code.
+pub const DBL_SHA2_256: u64 = 0x56;
+
+/// The size of a Bitcoin hash.
+const HASH_LEN: usize = 32;
+
+/// What a Bitcoin CID points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitcoinKind {
+    /// A block (header).
+    Block,
+    /// A transaction.
+    Tx,
+    /// A witness commitment.
+    WitnessCommitment,
+}
+
+impl BitcoinKind {
+    /// Returns the codec of the kind.
+    pub const fn codec(self) -> u64 {
+        match self {
+            Self::Block => BITCOIN_BLOCK,
+            Self::Tx => BITCOIN_TX,
+            Self::WitnessCommitment => BITCOIN_WITNESS_COMMITMENT,
+        }
+    }
+
+    fn from_codec(codec: u64) -> Result<Self> {
+        match codec {
+            BITCOIN_BLOCK => Ok(Self::Block),
+            BITCOIN_TX => Ok(Self::Tx),
+            BITCOIN_WITNESS_COMMITMENT => Ok(Self::WitnessCommitment),
+            _ => Err(Error::UnknownCodec),
+        }
+    }
+}
+
+/// Creates a CID from a hash in display form, i.e. byte-reversed hex.
+pub fn hash_to_cid<const S: usize>(kind: BitcoinKind, hash: &str) -> Result<Cid<S>> {
+    let bytes = Base::Base16Lower.decode(hash.to_ascii_lowercase())?;
+    let mut digest: [u8; HASH_LEN] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| Error::ParsingError)?;
+    digest.reverse();
+    Ok(Cid::new_v1(
+        kind.codec(),
+        Multihash::wrap(DBL_SHA2_256, &digest)?,
+    ))
+}
+
+/// Returns the kind and the hash in display form of a Bitcoin CID.
+pub fn cid_to_hash<const S: usize>(cid: &Cid<S>) -> Result<(BitcoinKind, String)> {
+    if cid.version() != Version::V1 {
+        return Err(Error::InvalidCidVersion);
+    }
+    let kind = BitcoinKind::from_codec(cid.codec())?;
+    if cid.hash().code() != DBL_SHA2_256 || cid.hash().digest().len() != HASH_LEN {
+        return Err(Error::ParsingError);
+    }
+    let mut digest = [0; HASH_LEN];
+    digest.copy_from_slice(cid.hash().digest());
+    digest.reverse();
+    Ok((kind, Base::Base16Lower.encode(digest)))
+}
+
+/// Creates the CID of a block from its hash in display form.
+pub fn block_hash_to_cid<const S: usize>(hash: &str) -> Result<Cid<S>> {
+    hash_to_cid(BitcoinKind::Block, hash)
+}
+
+/// Creates the CID of a transaction from its id in display form.
+pub fn tx_hash_to_cid<const S: usize>(hash: &str) -> Result<Cid<S>> {
+    hash_to_cid(BitcoinKind::Tx, hash)
+}
+
+/// Returns the block hash in display form of a block CID.
+pub fn cid_to_block_hash<const S: usize>(cid: &Cid<S>) -> Result<String> {
+    match cid_to_hash(cid)? {
+        (BitcoinKind::Block, hash) => Ok(hash),
+        _ => Err(Error::UnknownCodec),
+    }
+}
+
+/// Returns the transaction id in display form of a transaction CID.
+pub fn cid_to_tx_hash<const S: usize>(cid: &Cid<S>) -> Result<String> {
+    match cid_to_hash(cid)? {
+        (BitcoinKind::Tx, hash) => Ok(hash),
+        _ => Err(Error::UnknownCodec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS_BLOCK: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+    const GENESIS_TX: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    #[test]
+    fn byte_order() {
+        let cid: Cid<64> = block_hash_to_cid(GENESIS_BLOCK).unwrap();
+        assert_eq!(cid.codec(), BITCOIN_BLOCK);
+        assert_eq!(cid.hash().code(), DBL_SHA2_256);
+        let digest = cid.hash().digest();
+        assert_eq!((digest[0], digest[31]), (0x6f, 0x00));
+        assert_eq!(cid_to_block_hash(&cid).unwrap(), GENESIS_BLOCK);
+        assert!(cid_to_tx_hash(&cid).is_err());
+
+        let cid: Cid<64> = tx_hash_to_cid(&GENESIS_TX.to_ascii_uppercase()).unwrap();
+        assert_eq!(
+            cid_to_hash(&cid).unwrap(),
+            (BitcoinKind::Tx, GENESIS_TX.into())
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(block_hash_to_cid::<64>(&GENESIS_BLOCK[2..]).is_err());
+        assert!(block_hash_to_cid::<64>("zz").is_err());
+        let sha2 = Multihash::<64>::wrap(0x12, &[0; 32]).unwrap();
+        assert!(matches!(
+            cid_to_hash(&Cid::new_v1(BITCOIN_BLOCK, sha2)),
+            Err(Error::ParsingError)
+        ));
+        assert!(matches!(
+            cid_to_hash(&Cid::new_v1(0x55, sha2)),
+            Err(Error::UnknownCodec)
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+//! A Bloom filter for CIDs.
+//!
+//! The bit positions are derived directly from the multihash digest, hence no additional hashing
+//! is needed. Only the digest is taken into account, so CIDs with the same digest but different
+//! codecs or versions are considered to be the same block.
+use core::convert::TryInto;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+/// The length of the header of the binary representation.
+const HEADER_LEN: usize = 12;
+/// The maximum number of hash functions, more only make lookups slower.
+const MAX_HASHES: u32 = 64;
+
+/// A Bloom filter for CIDs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CidBloom {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl CidBloom {
+    /// Creates a filter sized for `expected_items` CIDs with the given false positive rate.
+    ///
+    /// The rate needs to be between 0 and 1 (exclusive).
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let items = expected_items.max(1) as f64;
+        let rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = core::f64::consts::LN_2;
+        let num_bits = (-items * rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let num_hashes = (num_bits as f64 / items * ln2).round() as u32;
+        Self::with_params(num_bits, num_hashes)
+    }
+
+    /// Creates a filter with an explicit number of bits and hash functions.
+    ///
+    /// The number of hash functions is clamped to between 1 and 64. Panics if the bits don't fit
+    /// into the address space.
+    pub fn with_params(num_bits: u64, num_hashes: u32) -> Self {
+        let num_bits = num_bits.max(1);
+        // Rounded up without adding, which could overflow.
+        let words = num_bits / 64 + u64::from(num_bits % 64 != 0);
+        let words = usize::try_from(words).expect("number of bits exceeds the address space");
+        Self {
+            bits: vec![0; words],
+            num_bits,
+            num_hashes: num_hashes.clamp(1, MAX_HASHES),
+        }
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// Returns the number of hash functions.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Adds a CID to the filter.
+    pub fn insert<const S: usize>(&mut self, cid: &Cid<S>) {
+        for index in self.indices(cid.hash().digest()) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+    }
+
+    /// Returns `true` if the CID was probably added, `false` if it definitely wasn't.
+    pub fn contains<const S: usize>(&self, cid: &Cid<S>) -> bool {
+        self.indices(cid.hash().digest())
+            .all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    /// Removes all CIDs.
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Returns the binary representation of the filter.
+    ///
+    /// It's the number of hashes (as little-endian `u32`), the number of bits (as little-endian
+    /// `u64`), followed by the bits as little-endian 64-bit words.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.bits.len() * 8);
+        bytes.extend_from_slice(&self.num_hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.num_bits.to_le_bytes());
+        for word in &self.bits {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Parses the binary representation of a filter.
+    ///
+    /// Fails with [`Error::ParsingError`] if there are more than 64 hash functions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::InputTooShort);
+        }
+        let num_hashes = u32::from_le_bytes(bytes[..4].try_into().expect("4 bytes"));
+        let num_bits = u64::from_le_bytes(bytes[4..HEADER_LEN].try_into().expect("8 bytes"));
+        let words = &bytes[HEADER_LEN..];
+        // Rounded up without adding, as the number of bits comes from the input.
+        let num_words = num_bits / 64 + u64::from(num_bits % 64 != 0);
+        if !(1..=MAX_HASHES).contains(&num_hashes)
+            || num_bits == 0
+            || words.len() as u64 != num_words * 8
+        {
+            return Err(Error::ParsingError);
+        }
+        let bits = words
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().expect("8 bytes")))
+            .collect();
+        Ok(Self {
+            bits,
+            num_bits,
+            num_hashes,
+        })
+    }
+
+    /// Returns the bit positions of a digest, using double hashing.
+    fn indices(&self, digest: &[u8]) -> impl Iterator<Item = u64> {
+        let (h1, h2) = if digest.len() >= 16 {
+            (
+                u64::from_le_bytes(digest[..8].try_into().expect("8 bytes")),
+                u64::from_le_bytes(digest[8..16].try_into().expect("8 bytes")),
+            )
+        } else {
+            // Short (most likely identity) digests are mixed, so that they spread evenly.
+            (
+                fnv1a(0xcbf2_9ce4_8422_2325, digest),
+                fnv1a(0x8422_2325_cbf2_9ce4, digest),
+            )
+        };
+        // An odd step size never cycles early, even if the number of bits is even.
+        let h2 = h2 | 1;
+        let num_bits = self.num_bits;
+        (0..u64::from(self.num_hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+/// The FNV-1a hash with a custom offset basis.
+fn fnv1a(basis: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(basis, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A filter is serialized as its binary representation.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CidBloom {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CidBloom {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes = serde_bytes::ByteBuf::deserialize(deserializer)?;
+        Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::CidBloom;
+    use crate::Cid;
+
+    fn cid(n: u32) -> Cid {
+        // A cheap stand-in for a cryptographic digest.
+        let digest: Vec<u8> = (0..32u32)
+            .map(|i| (n.wrapping_mul(2_654_435_761).rotate_left(i) ^ i) as u8)
+            .collect();
+        Cid::new_v1(0x55, Multihash::wrap(0x12, &digest).unwrap())
+    }
+
+    #[test]
+    fn false_positive_rate() {
+        let mut bloom = CidBloom::new(1000, 0.01);
+        for n in 0..1000 {
+            bloom.insert(&cid(n));
+        }
+        assert!((0..1000).all(|n| bloom.contains(&cid(n))));
+        let false_positives = (1000..11000).filter(|&n| bloom.contains(&cid(n))).count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+
+        let identity = Cid::new_v1(0x55, Multihash::wrap(0x00, b"foo").unwrap());
+        assert!(!bloom.contains(&identity));
+        bloom.insert(&identity);
+        assert!(bloom.contains(&identity));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut bloom = CidBloom::new(10, 0.1);
+        bloom.insert(&cid(1));
+        let bytes = bloom.to_bytes();
+        assert_eq!(CidBloom::from_bytes(&bytes).unwrap(), bloom);
+        assert!(CidBloom::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut huge = bytes[..4].to_vec();
+        huge.extend_from_slice(&u64::MAX.to_le_bytes());
+        huge.extend_from_slice(&[0; 8]);
+        assert!(CidBloom::from_bytes(&huge).is_err());
+
+        // Too many hash functions would make every lookup loop for ages.
+        let mut slow = bytes.clone();
+        slow[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(CidBloom::from_bytes(&slow).is_err());
+        slow[..4].copy_from_slice(&65u32.to_le_bytes());
+        assert!(CidBloom::from_bytes(&slow).is_err());
+        slow[..4].copy_from_slice(&64u32.to_le_bytes());
+        assert!(CidBloom::from_bytes(&slow).is_ok());
+    }
+
+    #[test]
+    fn params() {
+        let bloom = CidBloom::with_params(65, u32::MAX);
+        assert_eq!(bloom.num_hashes(), 64);
+        assert_eq!(bloom.to_bytes().len(), 12 + 2 * 8);
+        assert_eq!(CidBloom::from_bytes(&bloom.to_bytes()).unwrap(), bloom);
+        assert_eq!(CidBloom::with_params(64, 0).to_bytes().len(), 12 + 8);
+    }
+}
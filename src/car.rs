@@ -0,0 +1,361 @@
+//! Minimal support for the [CARv1] format.
+//!
+//! Only the header and the framing are handled, the blocks themselves are never decoded. This is
+//! enough to extract the roots of a CAR file or to list the CIDs it contains.
+//!
+//! The header is DAG-CBOR, but as its shape is fixed, it is encoded and decoded directly instead
+//! of depending on a full DAG-CBOR implementation.
+//!
+//! The [`index`] module contains the index formats of CARv2.
+//!
+//! [CARv1]: https://ipld.io/specs/transport/car/carv1/
+use std::convert::{TryFrom, TryInto};
+use std::io::{self, Read, Write};
+
+use unsigned_varint::encode as varint_encode;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::varint;
+
+pub mod index;
+
+/// The maximum size of a header that is accepted when reading, same as go-car.
+const MAX_HEADER_LEN: u64 = 32 * 1024 * 1024;
+
+/// The CBOR tag for CIDs.
+const CID_TAG: u64 = 42;
+
+/// CBOR major types.
+const MAJOR_UINT: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+
+/// The header of a CAR file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CarHeader<const S: usize = 64> {
+    version: u64,
+    roots: Vec<Cid<S>>,
+}
+
+impl<const S: usize> CarHeader<S> {
+    /// Creates a CARv1 header with the given roots.
+    pub fn new(roots: Vec<Cid<S>>) -> Self {
+        Self { version: 1, roots }
+    }
+
+    /// Returns the version of the CAR format.
+    ///
+    /// A CARv2 file starts with a header with version 2 and no roots.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the roots.
+    pub fn roots(&self) -> &[Cid<S>] {
+        &self.roots
+    }
+
+    /// Returns the DAG-CBOR encoded header, without the length prefix.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        // The keys are in DAG-CBOR order, shorter keys first.
+        write_type_len(&mut bytes, MAJOR_MAP, 2);
+        write_type_len(&mut bytes, MAJOR_TEXT, 5);
+        bytes.extend_from_slice(b"roots");
+        write_type_len(&mut bytes, MAJOR_ARRAY, self.roots.len() as u64);
+        for root in &self.roots {
+            write_type_len(&mut bytes, MAJOR_TAG, CID_TAG);
+            write_type_len(&mut bytes, MAJOR_BYTES, root.encoded_len() as u64 + 1);
+            // The multibase identity prefix.
+            bytes.push(0x00);
+            root.write_bytes(&mut bytes)
+                .expect("writing to a vec never fails");
+        }
+        write_type_len(&mut bytes, MAJOR_TEXT, 7);
+        bytes.extend_from_slice(b"version");
+        write_type_len(&mut bytes, MAJOR_UINT, self.version);
+        bytes
+    }
+
+    /// Parses a DAG-CBOR encoded header, without the length prefix.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder { bytes };
+        let mut version = None;
+        let mut roots = None;
+        for _ in 0..decoder.expect(MAJOR_MAP)? {
+            let key_len = decoder.expect(MAJOR_TEXT)?;
+            match decoder.take(key_len)? {
+                b"version" if version.is_none() => version = Some(decoder.expect(MAJOR_UINT)?),
+                b"roots" if roots.is_none() => {
+                    let len = decoder.expect(MAJOR_ARRAY)?;
+                    // Every root is at least a few bytes, don't trust the length for allocating.
+                    let mut cids = Vec::with_capacity(len.min(bytes.len() as u64) as usize);
+                    for _ in 0..len {
+                        cids.push(decoder.cid()?);
+                    }
+                    roots = Some(cids);
+                }
+                _ => return Err(Error::ParsingError),
+            }
+        }
+        if !decoder.bytes.is_empty() {
+            return Err(Error::ParsingError);
+        }
+        match (version, roots) {
+            (Some(1), Some(roots)) => Ok(Self { version: 1, roots }),
+            (Some(version @ 2..), None) => Ok(Self {
+                version,
+                roots: Vec::new(),
+            }),
+            _ => Err(Error::ParsingError),
+        }
+    }
+
+    /// Reads a length prefixed header, i.e. the start of a CAR file.
+    pub fn read<R: Read>(mut r: R) -> Result<Self> {
+        let len = varint::read_u64(&mut r)?;
+        if len > MAX_HEADER_LEN {
+            return Err(Error::ParsingError);
+        }
+        let mut bytes = vec![0; len as usize];
+        r.read_exact(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Writes a length prefixed header, returns the number of bytes written.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<usize> {
+        let bytes = self.to_bytes();
+        let mut buf = varint_encode::usize_buffer();
+        let len = varint_encode::usize(bytes.len(), &mut buf);
+        w.write_all(len)?;
+        w.write_all(&bytes)?;
+        Ok(len.len() + bytes.len())
+    }
+}
+
+/// Writes a frame of a CAR file, i.e. a CID and its block, returns the number of bytes written.
+pub fn write_frame<W: Write, const S: usize>(
+    mut w: W,
+    cid: &Cid<S>,
+    block: &[u8],
+) -> Result<usize> {
+    let frame_len = cid.encoded_len() + block.len();
+    let mut buf = varint_encode::usize_buffer();
+    let len = varint_encode::usize(frame_len, &mut buf);
+    w.write_all(len)?;
+    cid.write_bytes(&mut w)?;
+    w.write_all(block)?;
+    Ok(len.len() + frame_len)
+}
+
+/// Returns an iterator over the frames of a CAR file, which follow the header.
+///
+/// The iterator returns the CID and the length of the block, the blocks are skipped.
+pub fn frames<R: Read, const S: usize>(reader: R) -> Frames<R, S> {
+    Frames {
+        reader,
+        done: false,
+    }
+}
+
+/// An iterator over the frames of a CAR file, see [`frames`].
+#[derive(Debug)]
+pub struct Frames<R, const S: usize = 64> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read, const S: usize> Frames<R, S> {
+    fn read_frame(&mut self) -> Result<Option<(Cid<S>, u64)>> {
+        // The end of the file is only valid right before a frame.
+        let mut first = [0];
+        if self.reader.read(&mut first)? == 0 {
+            return Ok(None);
+        }
+        let frame_len = varint::read_u64((&first[..]).chain(&mut self.reader))?;
+        let mut frame = (&mut self.reader).take(frame_len);
+        let cid = Cid::read_bytes(&mut frame)?;
+        let block_len = frame_len
+            .checked_sub(cid.encoded_len() as u64)
+            .ok_or(Error::ParsingError)?;
+        if io::copy(&mut frame, &mut io::sink())? != block_len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(Some((cid, block_len)))
+    }
+}
+
+impl<R: Read, const S: usize> Iterator for Frames<R, S> {
+    type Item = Result<(Cid<S>, u64)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let frame = self.read_frame().transpose();
+        self.done = !matches!(frame, Some(Ok(_)));
+        frame
+    }
+}
+
+/// Writes the header of a CBOR item.
+fn write_type_len(bytes: &mut Vec<u8>, major: u8, len: u64) {
+    let major = major << 5;
+    match len {
+        0..=23 => bytes.push(major | len as u8),
+        24..=0xff => bytes.extend_from_slice(&[major | 24, len as u8]),
+        0x100..=0xffff => {
+            bytes.push(major | 25);
+            bytes.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(major | 26);
+            bytes.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(major | 27);
+            bytes.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+}
+
+/// A decoder for the small subset of DAG-CBOR that is used by the header.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: u64) -> Result<&'a [u8]> {
+        let len = usize::try_from(len).map_err(|_| Error::InputTooShort)?;
+        if self.bytes.len() < len {
+            return Err(Error::InputTooShort);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads the header of a CBOR item of the given major type, returns its argument.
+    fn expect(&mut self, major: u8) -> Result<u64> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != major {
+            return Err(Error::ParsingError);
+        }
+        let (len, min) = match initial & 0x1f {
+            len @ 0..=23 => return Ok(u64::from(len)),
+            24 => (u64::from(self.take(1)?[0]), 24),
+            25 => (
+                u64::from(u16::from_be_bytes(
+                    self.take(2)?.try_into().expect("2 bytes"),
+                )),
+                0x100,
+            ),
+            26 => (
+                u64::from(u32::from_be_bytes(
+                    self.take(4)?.try_into().expect("4 bytes"),
+                )),
+                0x1_0000,
+            ),
+            27 => (
+                u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")),
+                0x1_0000_0000,
+            ),
+            _ => return Err(Error::ParsingError),
+        };
+        // DAG-CBOR requires the shortest encoding.
+        if len < min {
+            return Err(Error::ParsingError);
+        }
+        Ok(len)
+    }
+
+    fn cid<const S: usize>(&mut self) -> Result<Cid<S>> {
+        if self.expect(MAJOR_TAG)? != CID_TAG {
+            return Err(Error::ParsingError);
+        }
+        let len = self.expect(MAJOR_BYTES)?;
+        match self.take(len)? {
+            // The whole byte string must be the CID, without any trailing bytes.
+            [0x00, bytes @ ..] => match Cid::try_from(bytes)? {
+                cid if cid.encoded_len() == bytes.len() => Ok(cid),
+                _ => Err(Error::ParsingError),
+            },
+            _ => Err(Error::ParsingError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::{frames, write_frame, CarHeader};
+    use crate::Cid;
+
+    #[test]
+    fn header() {
+        let roots = vec![
+            Cid::new_v0(Multihash::wrap(0x12, &[1; 32]).unwrap()).unwrap(),
+            Cid::new_v1(0x71, Multihash::wrap(0x12, &[2; 32]).unwrap()),
+        ];
+        let header = CarHeader::new(roots.clone());
+        let bytes = header.to_bytes();
+        assert!(bytes.starts_with(b"\xa2\x65roots\x82\xd8\x2a\x58\x23\x00\x12\x20"));
+        assert!(bytes.ends_with(b"\x67version\x01"));
+        assert_eq!(CarHeader::from_bytes(&bytes).unwrap(), header);
+
+        // The CARv2 pragma.
+        let pragma = CarHeader::<64>::from_bytes(b"\xa1\x67version\x02").unwrap();
+        assert_eq!(pragma.version(), 2);
+        assert!(pragma.roots().is_empty());
+
+        assert!(CarHeader::<64>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(CarHeader::<64>::from_bytes(b"\xa1\x67version\x01").is_err());
+        // Not the shortest encoding of the version.
+        assert!(CarHeader::<64>::from_bytes(b"\xa1\x67version\x18\x02").is_err());
+
+        // A single root, once without and once with a trailing byte after the CID.
+        let root = roots[1].to_bytes();
+        let with_root = |cid: &[u8]| {
+            let mut bytes = b"\xa2\x65roots\x81\xd8\x2a\x58".to_vec();
+            bytes.push(cid.len() as u8 + 1);
+            bytes.push(0x00);
+            bytes.extend_from_slice(cid);
+            bytes.extend_from_slice(b"\x67version\x01");
+            bytes
+        };
+        let parsed = CarHeader::<64>::from_bytes(&with_root(&root)).unwrap();
+        assert_eq!(parsed.roots(), &roots[1..]);
+        let trailing = [root, vec![0]].concat();
+        assert!(CarHeader::<64>::from_bytes(&with_root(&trailing)).is_err());
+    }
+
+    #[test]
+    fn car() {
+        let cids = [
+            Cid::new_v1(0x55, Multihash::wrap(0x12, &[1; 32]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, b"foo").unwrap()),
+        ];
+        let mut car = Vec::new();
+        let header = CarHeader::new(vec![cids[0]]);
+        header.write(&mut car).unwrap();
+        write_frame(&mut car, &cids[0], &[0xaa; 300]).unwrap();
+        write_frame(&mut car, &cids[1], b"").unwrap();
+
+        let mut reader = &car[..];
+        assert_eq!(CarHeader::read(&mut reader).unwrap(), header);
+        let all: Vec<_> = frames(&mut reader).map(Result::unwrap).collect();
+        assert_eq!(all, [(cids[0], 300), (cids[1], 0)]);
+
+        // Truncated block.
+        let mut reader = &car[..car.len() - 5];
+        CarHeader::<64>::read(&mut reader).unwrap();
+        let truncated: Vec<_> = frames::<_, 64>(reader).collect();
+        assert_eq!(truncated.len(), 2);
+        assert!(truncated[1].is_err());
+    }
+}
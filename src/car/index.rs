@@ -0,0 +1,298 @@
+//! The sorted index formats of [CARv2].
+//!
+//! Both formats map digests to the offsets of their frames. The digests are grouped into buckets
+//! of the same width, each bucket is a table of fixed size entries sorted by digest.
+//!
+//! [CARv2]: https://ipld.io/specs/transport/car/carv2/
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use multihash::Multihash;
+use unsigned_varint::encode as varint_encode;
+
+use crate::error::{Error, Result};
+use crate::varint;
+
+/// The multicodec code of the `IndexSorted` format.
+pub const INDEX_SORTED: u64 = 0x0400;
+/// The multicodec code of the `MultihashIndexSorted` format.
+pub const MULTIHASH_INDEX_SORTED: u64 = 0x0401;
+
+/// The size of the offset that follows the digest in each entry.
+const OFFSET_LEN: usize = 8;
+/// The maximum size of an entry, the size of a multihash digest fits into a byte.
+const MAX_WIDTH: usize = OFFSET_LEN + u8::MAX as usize;
+
+/// An index in the `IndexSorted` format, which maps digests to offsets.
+///
+/// The multihash codes are not part of the index, see [`MultihashIndexSorted`] for that.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IndexSorted {
+    offsets: BTreeMap<Vec<u8>, u64>,
+}
+
+impl IndexSorted {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of digests.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if the index contains no digests.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Adds a digest, returns the previous offset if it was already present.
+    pub fn insert(&mut self, digest: &[u8], offset: u64) -> Option<u64> {
+        self.offsets.insert(digest.to_vec(), offset)
+    }
+
+    /// Returns the offset of a digest.
+    pub fn get(&self, digest: &[u8]) -> Option<u64> {
+        self.offsets.get(digest).copied()
+    }
+
+    /// Returns an iterator over the digests and their offsets, sorted by digest.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], u64)> {
+        self.offsets
+            .iter()
+            .map(|(digest, &offset)| (digest.as_slice(), offset))
+    }
+
+    /// Reads an index, including its multicodec code.
+    pub fn read<R: Read>(mut r: R) -> Result<Self> {
+        if varint::read_u64(&mut r)? != INDEX_SORTED {
+            return Err(Error::UnknownCodec);
+        }
+        Self::read_buckets(r)
+    }
+
+    /// Writes the index, including its multicodec code, returns the number of bytes written.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<usize> {
+        let codec = write_varint(&mut w, INDEX_SORTED)?;
+        Ok(codec + self.write_buckets(w)?)
+    }
+
+    fn read_buckets<R: Read>(mut r: R) -> Result<Self> {
+        let mut index = Self::new();
+        for _ in 0..read_u32(&mut r)? {
+            let width = usize::try_from(read_u32(&mut r)?).map_err(|_| Error::ParsingError)?;
+            let len = read_u64(&mut r)?;
+            // The width is checked before allocating an entry of that size.
+            if !(OFFSET_LEN..=MAX_WIDTH).contains(&width) || len % width as u64 != 0 {
+                return Err(Error::ParsingError);
+            }
+            let mut entry = vec![0; width];
+            for _ in 0..len / width as u64 {
+                r.read_exact(&mut entry)?;
+                let (digest, offset) = entry.split_at(width - OFFSET_LEN);
+                let offset = u64::from_le_bytes(offset.try_into().expect("8 bytes"));
+                index.offsets.insert(digest.to_vec(), offset);
+            }
+        }
+        Ok(index)
+    }
+
+    fn write_buckets<W: Write>(&self, mut w: W) -> Result<usize> {
+        let mut buckets: BTreeMap<usize, Vec<(&[u8], u64)>> = BTreeMap::new();
+        for (digest, offset) in self.iter() {
+            buckets
+                .entry(digest.len() + OFFSET_LEN)
+                .or_default()
+                .push((digest, offset));
+        }
+
+        w.write_all(&(buckets.len() as u32).to_le_bytes())?;
+        let mut written = 4;
+        for (width, entries) in buckets {
+            let len = width * entries.len();
+            w.write_all(&(width as u32).to_le_bytes())?;
+            w.write_all(&(len as u64).to_le_bytes())?;
+            for (digest, offset) in entries {
+                w.write_all(digest)?;
+                w.write_all(&offset.to_le_bytes())?;
+            }
+            written += 12 + len;
+        }
+        Ok(written)
+    }
+}
+
+/// An index in the `MultihashIndexSorted` format, which maps multihashes to offsets.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MultihashIndexSorted {
+    indexes: BTreeMap<u64, IndexSorted>,
+}
+
+impl MultihashIndexSorted {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of multihashes.
+    pub fn len(&self) -> usize {
+        self.indexes.values().map(IndexSorted::len).sum()
+    }
+
+    /// Returns `true` if the index contains no multihashes.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.values().all(IndexSorted::is_empty)
+    }
+
+    /// Adds a multihash, returns the previous offset if it was already present.
+    pub fn insert<const S: usize>(&mut self, hash: &Multihash<S>, offset: u64) -> Option<u64> {
+        self.indexes
+            .entry(hash.code())
+            .or_default()
+            .insert(hash.digest(), offset)
+    }
+
+    /// Returns the offset of a multihash.
+    pub fn get<const S: usize>(&self, hash: &Multihash<S>) -> Option<u64> {
+        self.indexes.get(&hash.code())?.get(hash.digest())
+    }
+
+    /// Returns the per multihash code indexes, sorted by code.
+    pub fn indexes(&self) -> impl Iterator<Item = (u64, &IndexSorted)> {
+        self.indexes.iter().map(|(&code, index)| (code, index))
+    }
+
+    /// Reads an index, including its multicodec code.
+    pub fn read<R: Read>(mut r: R) -> Result<Self> {
+        if varint::read_u64(&mut r)? != MULTIHASH_INDEX_SORTED {
+            return Err(Error::UnknownCodec);
+        }
+        Self::read_indexes(r)
+    }
+
+    /// Writes the index, including its multicodec code, returns the number of bytes written.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<usize> {
+        let mut written = write_varint(&mut w, MULTIHASH_INDEX_SORTED)?;
+        w.write_all(&(self.indexes.len() as u32).to_le_bytes())?;
+        written += 4;
+        for (code, index) in &self.indexes {
+            w.write_all(&code.to_le_bytes())?;
+            written += 8 + index.write_buckets(&mut w)?;
+        }
+        Ok(written)
+    }
+
+    fn read_indexes<R: Read>(mut r: R) -> Result<Self> {
+        let mut indexes = BTreeMap::new();
+        for _ in 0..read_u32(&mut r)? {
+            let code = read_u64(&mut r)?;
+            indexes.insert(code, IndexSorted::read_buckets(&mut r)?);
+        }
+        Ok(Self { indexes })
+    }
+}
+
+/// A CARv2 index in one of the supported formats.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CarIndex {
+    /// An index in the `IndexSorted` format.
+    IndexSorted(IndexSorted),
+    /// An index in the `MultihashIndexSorted` format.
+    MultihashIndexSorted(MultihashIndexSorted),
+}
+
+impl CarIndex {
+    /// Reads an index in any of the supported formats.
+    pub fn read<R: Read>(mut r: R) -> Result<Self> {
+        match varint::read_u64(&mut r)? {
+            INDEX_SORTED => Ok(Self::IndexSorted(IndexSorted::read_buckets(r)?)),
+            MULTIHASH_INDEX_SORTED => Ok(Self::MultihashIndexSorted(
+                MultihashIndexSorted::read_indexes(r)?,
+            )),
+            _ => Err(Error::UnknownCodec),
+        }
+    }
+
+    /// Writes the index, returns the number of bytes written.
+    pub fn write<W: Write>(&self, w: W) -> Result<usize> {
+        match self {
+            Self::IndexSorted(index) => index.write(w),
+            Self::MultihashIndexSorted(index) => index.write(w),
+        }
+    }
+}
+
+fn read_u32<R: Read>(mut r: R) -> Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(mut r: R) -> Result<u64> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_varint<W: Write>(mut w: W, value: u64) -> Result<usize> {
+    let mut buf = varint_encode::u64_buffer();
+    let bytes = varint_encode::u64(value, &mut buf);
+    w.write_all(bytes)?;
+    Ok(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::{CarIndex, IndexSorted, MultihashIndexSorted};
+
+    #[test]
+    fn index_sorted() {
+        let mut index = IndexSorted::new();
+        index.insert(&[2; 32], 100);
+        index.insert(&[1; 32], 200);
+        index.insert(b"foo", 300);
+
+        let mut bytes = Vec::new();
+        let written = index.write(&mut bytes).unwrap();
+        assert_eq!(written, bytes.len());
+        // The codec, two buckets, the first one with width 3 + 8 and one entry.
+        assert!(bytes.starts_with(b"\x80\x08\x02\x00\x00\x00\x0b\x00\x00\x00\x0b\x00"));
+        assert!(bytes.ends_with(&[[2; 32].as_slice(), &100u64.to_le_bytes()].concat()));
+
+        let parsed = IndexSorted::read(&bytes[..]).unwrap();
+        assert_eq!(parsed, index);
+        assert_eq!(parsed.get(&[1; 32]), Some(200));
+        assert_eq!(parsed.get(&[3; 32]), None);
+        assert!(IndexSorted::read(&bytes[..bytes.len() - 1]).is_err());
+        assert!(MultihashIndexSorted::read(&bytes[..]).is_err());
+
+        // A single bucket of 4 GiB wide entries.
+        let huge = b"\x80\x08\x01\x00\x00\x00\xff\xff\xff\xff\xff\xff\xff\xff\x00\x00\x00\x00";
+        assert!(IndexSorted::read(&huge[..]).is_err());
+    }
+
+    #[test]
+    fn multihash_index_sorted() {
+        let sha2 = Multihash::<64>::wrap(0x12, &[1; 32]).unwrap();
+        let identity = Multihash::<64>::wrap(0x00, &[1; 32]).unwrap();
+        let mut index = MultihashIndexSorted::new();
+        index.insert(&sha2, 1);
+        index.insert(&identity, 2);
+        assert_eq!(index.len(), 2);
+
+        let mut bytes = Vec::new();
+        index.write(&mut bytes).unwrap();
+        match CarIndex::read(&bytes[..]).unwrap() {
+            CarIndex::MultihashIndexSorted(parsed) => {
+                assert_eq!(parsed.get(&sha2), Some(1));
+                assert_eq!(parsed.get(&identity), Some(2));
+                assert_eq!(parsed, index);
+            }
+            CarIndex::IndexSorted(_) => panic!("wrong index format"),
+        }
+    }
+}
@@ -5,7 +5,10 @@
 //!
 //! As a library author that works with CIDs that should support hashes of anysize, you would
 //! import the `Cid` type from this module.
-use core::convert::TryFrom;
+use core::cmp::Ordering;
+use core::convert::{TryFrom, TryInto};
+#[cfg(feature = "alloc")]
+use core::fmt;
 
 #[cfg(feature = "alloc")]
 use multibase::{encode as base_encode, Base};
@@ -23,50 +26,44 @@ use alloc::{
     vec::Vec,
 };
 
-#[cfg(feature = "std")]
-pub(crate) use unsigned_varint::io::read_u64 as varint_read_u64;
-
-/// Reads 64 bits from a byte array into a u64
-/// Adapted from unsigned-varint's generated read_u64 function at
-/// https://github.com/paritytech/unsigned-varint/blob/master/src/io.rs
-#[cfg(not(feature = "std"))]
-pub(crate) fn varint_read_u64<R: io::Read>(mut r: R) -> Result<u64> {
-    use unsigned_varint::decode;
-    let mut b = varint_encode::u64_buffer();
-    for i in 0..b.len() {
-        let n = r.read(&mut (b[i..i + 1]))?;
-        if n == 0 {
-            return Err(Error::VarIntDecodeError);
-        } else if decode::is_last(b[i]) {
-            match decode::u64(&b[..=i]) {
-                Ok((value, _)) => return Ok(value),
-                Err(_) => return Err(Error::VarIntDecodeError),
-            }
-        }
-    }
-    Err(Error::VarIntDecodeError)
-}
-
 #[cfg(feature = "std")]
 use std::io;
 
 #[cfg(not(feature = "std"))]
 use core2::io;
 
+#[cfg(feature = "alloc")]
+use crate::base32;
+#[cfg(feature = "alloc")]
+use crate::base58;
 use crate::error::{Error, Result};
+use crate::trace;
+use crate::varint;
 use crate::version::Version;
 
 /// DAG-PB multicodec code
-const DAG_PB: u64 = 0x70;
+pub(crate) const DAG_PB: u64 = 0x70;
 /// The SHA_256 multicodec code
 pub(crate) const SHA2_256: u64 = 0x12;
+/// The `libp2p-key` multicodec code, used for CIDs of peer IDs and IPNS names.
+pub const LIBP2P_KEY: u64 = 0x72;
+
+/// The length of the version, codec, multihash code and digest size of a database key.
+const DB_KEY_HEADER_LEN: usize = 18;
 
 /// Representation of a CID.
 ///
 /// The generic is about the allocated size of the multihash.
-#[derive(Copy, PartialEq, Eq, Clone, PartialOrd, Ord, Hash)]
+#[allow(clippy::derived_hash_with_manual_eq)]
+#[derive(Copy, Clone, Hash)]
 #[cfg_attr(feature = "scale-codec", derive(parity_scale_codec::Decode))]
 #[cfg_attr(feature = "scale-codec", derive(parity_scale_codec::Encode))]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Binary))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub struct Cid<const S: usize> {
     /// The version of CID.
     version: Version,
@@ -124,6 +121,21 @@ impl<const S: usize> Cid<S> {
         }
     }
 
+    /// Convert a CIDv1 to a CIDv0. Returns unchanged if already a CIDv0.
+    ///
+    /// Only dag-pb CIDs with a 32 byte SHA2-256 multihash can be represented as CIDv0.
+    pub fn try_into_v0(self) -> Result<Self> {
+        match self.version {
+            Version::V0 => Ok(self),
+            Version::V1 => {
+                if self.codec != DAG_PB {
+                    return Err(Error::InvalidCidV0Codec);
+                }
+                Self::new_v0(self.hash)
+            }
+        }
+    }
+
     /// Returns the cid version.
     pub const fn version(&self) -> Version {
         self.version
@@ -139,10 +151,22 @@ impl<const S: usize> Cid<S> {
         &self.hash
     }
 
+    /// Returns the version, codec and multihash of the cid.
+    pub const fn into_parts(self) -> (Version, u64, Multihash<S>) {
+        (self.version, self.codec, self.hash)
+    }
+
+    /// Create a CID from its version, codec and multihash, the inverse of [`Cid::into_parts`].
+    ///
+    /// The parts are validated like with [`Cid::new`].
+    pub const fn from_parts(version: Version, codec: u64, hash: Multihash<S>) -> Result<Self> {
+        Self::new(version, codec, hash)
+    }
+
     /// Reads the bytes from a byte stream.
     pub fn read_bytes<R: io::Read>(mut r: R) -> Result<Self> {
-        let version = varint_read_u64(&mut r)?;
-        let codec = varint_read_u64(&mut r)?;
+        let version = varint::read_u64(&mut r)?;
+        let codec = varint::read_u64(&mut r)?;
 
         // CIDv0 has the fixed `0x12 0x20` prefix
         if [version, codec] == [0x12, 0x20] {
@@ -162,6 +186,158 @@ impl<const S: usize> Cid<S> {
         }
     }
 
+    /// Reads the bytes from a byte stream, also returns how many bytes were read.
+    ///
+    /// Only minimal varints are accepted and nothing is read past the CID, hence the number of
+    /// bytes read is always the [`Cid::encoded_len`] of the CID.
+    pub fn read_bytes_with_consumed<R: io::Read>(r: R) -> Result<(Self, usize)> {
+        let cid = Self::read_bytes(r)?;
+        Ok((cid, cid.encoded_len()))
+    }
+
+    /// Reads only the version, codec and multihash code from a byte stream.
+    ///
+    /// The digest is neither read nor validated, e.g. to route blocks by codec before fully
+    /// decoding their CID. Of a CIDv0 only the two bytes of the multihash prefix are read.
+    pub fn peek_header<R: io::Read>(mut r: R) -> Result<(Version, u64, u64)> {
+        let version = varint::read_u64(&mut r)?;
+        let codec = varint::read_u64(&mut r)?;
+
+        // CIDv0 has the fixed `0x12 0x20` prefix
+        if [version, codec] == [SHA2_256, 0x20] {
+            return Ok((Version::V0, DAG_PB, SHA2_256));
+        }
+
+        match Version::try_from(version)? {
+            Version::V0 => Err(Error::InvalidExplicitCidV0),
+            Version::V1 => Ok((Version::V1, codec, varint::read_u64(r)?)),
+        }
+    }
+
+    /// Parses a string that is exactly a CID, i.e. multibase encoded or a base58btc CIDv0.
+    ///
+    /// Unlike [`Cid::from_ipfs_path`], paths and URLs containing `/ipfs/` are rejected.
+    ///
+    /// The base16, base32 and base36 encodings are case-insensitive, the case of the string
+    /// doesn't need to match the multibase prefix. E.g. a CID in the label of a subdomain gateway
+    /// hostname, whose case DNS doesn't preserve, is parsed whatever its case.
+    #[cfg(feature = "alloc")]
+    pub fn parse_strict(cid_str: &str) -> Result<Self> {
+        Self::decode_str(cid_str).map_err(|error| {
+            trace::parse_failure("str", &error, cid_str.len(), None);
+            error
+        })
+    }
+
+    /// Parses a string that is exactly a CID in its canonical form.
+    ///
+    /// That is the form of [`ToString::to_string`], base58btc for CIDv0 and lowercase base32 for
+    /// CIDv1, e.g. to avoid duplicate keys for the same CID. Fails with [`Error::NotCanonical`] if
+    /// the string is a valid CID in any other form.
+    #[cfg(feature = "alloc")]
+    pub fn parse_canonical(cid_str: &str) -> Result<Self> {
+        let cid = Self::parse_strict(cid_str)?;
+        if cid.to_string() != cid_str {
+            trace::parse_failure("str", &Error::NotCanonical, cid_str.len(), None);
+            return Err(Error::NotCanonical);
+        }
+        Ok(cid)
+    }
+
+    /// Returns whether the string is a CID in its canonical form, see [`Cid::parse_canonical`].
+    #[cfg(feature = "alloc")]
+    pub fn is_canonical_str(cid_str: &str) -> bool {
+        Self::parse_canonical(cid_str).is_ok()
+    }
+
+    /// Parses a CID, or the CID after the `/ipfs/` of a path or URL.
+    ///
+    /// E.g. `/ipfs/<cid>` or `https://ipfs.io/ipfs/<cid>`. This is what [`Cid::try_from`] parses
+    /// if the `ipfs-path` feature is enabled.
+    #[cfg(feature = "alloc")]
+    pub fn from_ipfs_path(path: &str) -> Result<Self> {
+        Self::parse_ipfs_path(path).map_err(|error| {
+            trace::parse_failure("path", &error, path.len(), None);
+            error
+        })
+    }
+
+    /// Parses a CID in a path or URL, without recording failures.
+    #[cfg(feature = "alloc")]
+    fn parse_ipfs_path(path: &str) -> Result<Self> {
+        static IPFS_DELIMETER: &str = "/ipfs/";
+
+        let hash = match path.find(IPFS_DELIMETER) {
+            Some(index) => &path[index + IPFS_DELIMETER.len()..],
+            _ => path,
+        };
+        Self::decode_str(hash)
+    }
+
+    /// Parses a string, without recording failures.
+    #[cfg(feature = "alloc")]
+    fn parse_str(cid_str: &str) -> Result<Self> {
+        if cfg!(feature = "ipfs-path") {
+            Self::parse_ipfs_path(cid_str)
+        } else {
+            Self::decode_str(cid_str)
+        }
+    }
+
+    /// Parses a string that is exactly a CID, also returns the multibase it was encoded with.
+    ///
+    /// The base is `None` for a CIDv0, which isn't multibase encoded but always base58btc. E.g.
+    /// gateways use the base to respond with CIDs encoded like the requested one.
+    ///
+    /// ```
+    /// use cid::Cid;
+    /// use multibase::Base;
+    ///
+    /// let requested = "BAFKREIBME22GW2H7Y2H7TG2FHQOTAQJUCNBC24DEQO72B6MKL2EGEZXHVY";
+    /// let (cid, base) = Cid::from_str_with_base(requested).unwrap();
+    /// assert_eq!(base, Some(Base::Base32Upper));
+    /// assert_eq!(cid.to_string_of_base(base.unwrap()).unwrap(), requested);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn from_str_with_base(cid_str: &str) -> Result<(Self, Option<Base>)> {
+        Self::decode_str_with_base(cid_str).map_err(|error| {
+            trace::parse_failure("str", &error, cid_str.len(), None);
+            error
+        })
+    }
+
+    /// Decodes a string that is exactly a CID, without recording failures.
+    #[cfg(feature = "alloc")]
+    fn decode_str(hash: &str) -> Result<Self> {
+        Self::decode_str_with_base(hash).map(|(cid, _)| cid)
+    }
+
+    /// Decodes a string that is exactly a CID and its base, without recording failures.
+    #[cfg(feature = "alloc")]
+    fn decode_str_with_base(hash: &str) -> Result<(Self, Option<Base>)> {
+        if hash.len() < 2 {
+            return Err(Error::InputTooShort);
+        }
+
+        // The string was already identified as CIDv0, hence the binary prefix doesn't need to be
+        // parsed again.
+        if Version::is_v0_str(hash) {
+            if let Some(decoded) = base58::decode_v0(hash) {
+                if Version::is_v0_binary(&decoded) {
+                    let mh = Multihash::wrap(SHA2_256, &decoded[2..])?;
+                    return Ok((Self::new_v0(mh)?, None));
+                }
+            }
+            // Not a valid CIDv0, fall back to the generic decoder for a proper error.
+            trace::slow_path("invalid CIDv0 string");
+            let decoded = Base::Base58Btc.decode(hash)?;
+            return Ok((Self::read_bytes(decoded.as_slice())?, None));
+        }
+
+        let (base, decoded) = multibase::decode(hash)?;
+        Ok((Self::read_bytes(decoded.as_slice())?, Some(base)))
+    }
+
     fn write_bytes_v1<W: io::Write>(&self, mut w: W) -> Result<usize> {
         let mut version_buf = varint_encode::u64_buffer();
         let version = varint_encode::u64(self.version.into(), &mut version_buf);
@@ -188,40 +364,44 @@ impl<const S: usize> Cid<S> {
     }
 
     /// Returns the length in bytes needed to encode this cid into bytes.
-    pub fn encoded_len(&self) -> usize {
+    ///
+    /// This is exactly the number of bytes [`Cid::write_bytes`] writes.
+    pub const fn encoded_len(&self) -> usize {
+        let size = self.hash.size();
+        let hash_len = varint::encoded_len(self.hash.code())
+            + varint::encoded_len(size as u64)
+            + size as usize;
         match self.version {
-            Version::V0 => self.hash.encoded_len(),
-            Version::V1 => {
-                let mut version_buf = varint_encode::u64_buffer();
-                let version = varint_encode::u64(self.version.into(), &mut version_buf);
-
-                let mut codec_buf = varint_encode::u64_buffer();
-                let codec = varint_encode::u64(self.codec, &mut codec_buf);
-
-                version.len() + codec.len() + self.hash.encoded_len()
-            }
+            Version::V0 => hash_len,
+            // The version 1 is always encoded in a single byte.
+            Version::V1 => 1 + varint::encoded_len(self.codec) + hash_len,
         }
     }
 
     /// Returns the encoded bytes of the `Cid`.
     #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+        let mut bytes = Vec::with_capacity(self.encoded_len());
         let written = self.write_bytes(&mut bytes).unwrap();
         debug_assert_eq!(written, bytes.len());
         bytes
     }
 
+    /// Returns the binary CIDv0, whose digest is always 32 bytes.
     #[cfg(feature = "alloc")]
     #[allow(clippy::wrong_self_convention)]
-    fn to_string_v0(&self) -> String {
-        Base::Base58Btc.encode(self.hash.to_bytes())
+    fn to_bytes_v0(&self) -> [u8; base58::V0_BINARY_LEN] {
+        let mut bytes = [0u8; base58::V0_BINARY_LEN];
+        bytes[0] = SHA2_256 as u8;
+        bytes[1] = 32;
+        bytes[2..].copy_from_slice(self.hash.digest());
+        bytes
     }
 
     #[cfg(feature = "alloc")]
     #[allow(clippy::wrong_self_convention)]
-    fn to_string_v1(&self) -> String {
-        multibase::encode(Base::Base32Lower, self.to_bytes().as_slice())
+    fn to_string_v0(&self) -> String {
+        base58::encode_v0(&self.to_bytes_v0())
     }
 
     /// Convert CID into a multibase encoded string
@@ -249,9 +429,189 @@ impl<const S: usize> Cid<S> {
                     Err(Error::InvalidCidV0Base)
                 }
             }
-            Version::V1 => Ok(base_encode(base, self.to_bytes())),
+            Version::V1 => match base {
+                Base::Base32Lower | Base::Base32Upper => {
+                    // The prefix and the unpadded base32 of the binary CID.
+                    let mut output = String::with_capacity(1 + (self.encoded_len() * 8 + 4) / 5);
+                    self.write_str_base32(base == Base::Base32Upper, &mut output)
+                        .expect("writing to a string never fails");
+                    Ok(output)
+                }
+                _ => Ok(base_encode(base, self.to_bytes())),
+            },
+        }
+    }
+
+    /// Writes the multibase encoded CID into a [`fmt::Write`], e.g. into an existing buffer.
+    ///
+    /// The output is the same as the one of [`Cid::to_string_of_base`], but for base32 and the
+    /// base58btc of CIDv0 nothing is allocated.
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    ///
+    /// use cid::Cid;
+    /// use multibase::Base;
+    ///
+    /// let cid = Cid::try_from("bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy").unwrap();
+    /// let mut url = String::from("https://dweb.link/ipfs/");
+    /// cid.write_str_of_base(Base::Base32Lower, &mut url).unwrap();
+    /// url.write_str("/index.html").unwrap();
+    /// assert_eq!(url, format!("https://dweb.link/ipfs/{}/index.html", cid));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn write_str_of_base<W: fmt::Write>(&self, base: Base, mut w: W) -> Result<()> {
+        let written = match (self.version, base) {
+            (Version::V0, Base::Base58Btc) => base58::write_v0(&self.to_bytes_v0(), &mut w),
+            (Version::V0, _) => return Err(Error::InvalidCidV0Base),
+            (Version::V1, Base::Base32Lower | Base::Base32Upper) => {
+                self.write_str_base32(base == Base::Base32Upper, &mut w)
+            }
+            (Version::V1, _) => w.write_str(&base_encode(base, self.to_bytes())),
+        };
+        written.map_err(|_| Error::Io(io::ErrorKind::Other.into()))
+    }
+
+    /// Writes the multibase encoded CIDv1 in base32.
+    #[cfg(feature = "alloc")]
+    fn write_str_base32<W: fmt::Write>(&self, uppercase: bool, mut w: W) -> fmt::Result {
+        w.write_char(if uppercase { 'B' } else { 'b' })?;
+        let mut encoder = base32::Encoder::new(&mut w, uppercase);
+        self.write_bytes(&mut encoder).map_err(|_| fmt::Error)?;
+        encoder.finish()
+    }
+
+    /// Returns the CID as CIDv1 in lowercase base36, the recommended encoding for DNS labels.
+    ///
+    /// A CIDv0 is converted to CIDv1, as CIDv0 are always base58btc encoded.
+    #[cfg(feature = "alloc")]
+    pub fn to_string_base36(&self) -> String {
+        // CIDv0 always have the dag-pb codec, hence the CIDv1 is the same with just the version.
+        let v1 = Self::new_v1(self.codec, self.hash);
+        base_encode(Base::Base36Lower, v1.to_bytes())
+    }
+
+    /// Returns the key to store the block of this CID under, which is the multihash.
+    ///
+    /// The codec and version are not part of the key, so that e.g. a CIDv0 and a CIDv1 for the
+    /// same bytes refer to the same stored block.
+    #[cfg(feature = "alloc")]
+    pub fn to_block_key(&self) -> Vec<u8> {
+        self.hash.to_bytes()
+    }
+
+    /// Creates a CIDv1 with the given codec from a key returned by [`Cid::to_block_key`].
+    pub fn from_block_key(codec: u64, key: &[u8]) -> Result<Self> {
+        let hash = Multihash::from_bytes(key)?;
+        Ok(Self::new_v1(codec, hash))
+    }
+
+    /// The length of the keys returned by [`Cid::to_db_key`].
+    pub const DB_KEY_LEN: usize = DB_KEY_HEADER_LEN + S;
+
+    /// Returns a fixed-width key for key-value stores.
+    ///
+    /// The key is the version (1 byte), the codec and the multihash code (as big-endian `u64`),
+    /// the digest size (1 byte) and the digest padded with zeros to `S` bytes. Comparing the keys
+    /// byte-wise gives the same order as comparing the CIDs.
+    #[cfg(feature = "alloc")]
+    pub fn to_db_key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(Self::DB_KEY_LEN);
+        key.push(u64::from(self.version) as u8);
+        key.extend_from_slice(&self.codec.to_be_bytes());
+        key.extend_from_slice(&self.hash.code().to_be_bytes());
+        key.push(self.hash.size());
+        key.extend_from_slice(self.hash.digest());
+        key.resize(Self::DB_KEY_LEN, 0);
+        key
+    }
+
+    /// Parses a key returned by [`Cid::to_db_key`].
+    pub fn from_db_key(key: &[u8]) -> Result<Self> {
+        if key.len() < Self::DB_KEY_LEN {
+            return Err(Error::InputTooShort);
+        }
+        if key.len() > Self::DB_KEY_LEN {
+            return Err(Error::ParsingError);
+        }
+        let version = Version::try_from(u64::from(key[0]))?;
+        let codec = u64::from_be_bytes(key[1..9].try_into().expect("8 bytes"));
+        let code = u64::from_be_bytes(key[9..17].try_into().expect("8 bytes"));
+        let size = usize::from(key[17]);
+        if size > S {
+            return Err(Error::ParsingError);
+        }
+        let (digest, padding) = key[DB_KEY_HEADER_LEN..].split_at(size);
+        if padding.iter().any(|&byte| byte != 0) {
+            return Err(Error::ParsingError);
+        }
+        let hash = Multihash::wrap(code, digest)?;
+        Self::new(version, codec, hash)
+    }
+}
+
+/// A builder for CIDs, e.g. for generic code that doesn't know the version upfront.
+///
+/// The version defaults to CIDv1, the codec to dag-pb for CIDv0. [`CidBuilder::build`] validates
+/// the parts like [`Cid::new`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CidBuilder<const S: usize> {
+    version: Option<Version>,
+    codec: Option<u64>,
+    hash: Option<Multihash<S>>,
+}
+
+impl<const S: usize> CidBuilder<S> {
+    /// Creates a builder with no parts set.
+    pub const fn new() -> Self {
+        Self {
+            version: None,
+            codec: None,
+            hash: None,
         }
     }
+
+    /// Sets the version.
+    pub const fn version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the codec.
+    pub const fn codec(mut self, codec: u64) -> Self {
+        self.codec = Some(codec);
+        self
+    }
+
+    /// Sets the multihash.
+    pub const fn hash(mut self, hash: Multihash<S>) -> Self {
+        self.hash = Some(hash);
+        self
+    }
+
+    /// Creates the CID.
+    ///
+    /// Fails with [`Error::MissingBuilderField`] if the multihash or, for CIDv1, the codec isn't
+    /// set, and like [`Cid::new`] if the parts don't form a valid CID.
+    pub fn build(self) -> Result<Cid<S>> {
+        let version = self.version.unwrap_or(Version::V1);
+        let codec = match (version, self.codec) {
+            (_, Some(codec)) => codec,
+            (Version::V0, None) => DAG_PB,
+            (Version::V1, None) => return Err(Error::MissingBuilderField("codec")),
+        };
+        let hash = self.hash.ok_or(Error::MissingBuilderField("hash"))?;
+        Cid::new(version, codec, hash)
+    }
+}
+
+impl<const S: usize> From<Cid<S>> for CidBuilder<S> {
+    fn from(cid: Cid<S>) -> Self {
+        Self::new()
+            .version(cid.version)
+            .codec(cid.codec)
+            .hash(cid.hash)
+    }
 }
 
 impl<const S: usize> Default for Cid<S> {
@@ -264,16 +624,44 @@ impl<const S: usize> Default for Cid<S> {
     }
 }
 
+// Only the actual digest is compared and not the whole allocated space of the multihash.
+impl<const S: usize> PartialEq for Cid<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+            && self.codec == other.codec
+            && self.hash.code() == other.hash.code()
+            && self.hash.digest() == other.hash.digest()
+    }
+}
+
+impl<const S: usize> Eq for Cid<S> {}
+
+impl<const S: usize> PartialOrd for Cid<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const S: usize> Ord for Cid<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.version
+            .cmp(&other.version)
+            .then_with(|| self.codec.cmp(&other.codec))
+            .then_with(|| self.hash.code().cmp(&other.hash.code()))
+            .then_with(|| self.hash.size().cmp(&other.hash.size()))
+            .then_with(|| self.hash.digest().cmp(other.hash.digest()))
+    }
+}
+
 // TODO: remove the dependency on alloc by fixing
 // https://github.com/multiformats/rust-multibase/issues/33
 #[cfg(feature = "alloc")]
-impl<const S: usize> core::fmt::Display for Cid<S> {
-    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        let output = match self.version {
-            Version::V0 => self.to_string_v0(),
-            Version::V1 => self.to_string_v1(),
-        };
-        write!(f, "{}", output)
+impl<const S: usize> fmt::Display for Cid<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Version::V0 => base58::write_v0(&self.to_bytes_v0(), f),
+            Version::V1 => self.write_str_base32(false, f),
+        }
     }
 }
 
@@ -287,11 +675,7 @@ impl<const S: usize> core::fmt::Debug for Cid<S> {
                 .field("hash", self.hash())
                 .finish()
         } else {
-            let output = match self.version {
-                Version::V0 => self.to_string_v0(),
-                Version::V1 => self.to_string_v1(),
-            };
-            write!(f, "Cid({})", output)
+            write!(f, "Cid({})", self)
         }
     }
 }
@@ -319,25 +703,10 @@ impl<const S: usize> TryFrom<&str> for Cid<S> {
     type Error = Error;
 
     fn try_from(cid_str: &str) -> Result<Self> {
-        static IPFS_DELIMETER: &str = "/ipfs/";
-
-        let hash = match cid_str.find(IPFS_DELIMETER) {
-            Some(index) => &cid_str[index + IPFS_DELIMETER.len()..],
-            _ => cid_str,
-        };
-
-        if hash.len() < 2 {
-            return Err(Error::InputTooShort);
-        }
-
-        let decoded = if Version::is_v0_str(hash) {
-            Base::Base58Btc.decode(hash)?
-        } else {
-            let (_, decoded) = multibase::decode(hash)?;
-            decoded
-        };
-
-        Self::try_from(decoded)
+        Self::parse_str(cid_str).map_err(|error| {
+            trace::parse_failure("str", &error, cid_str.len(), None);
+            error
+        })
     }
 }
 
@@ -353,8 +722,13 @@ impl<const S: usize> TryFrom<Vec<u8>> for Cid<S> {
 impl<const S: usize> TryFrom<&[u8]> for Cid<S> {
     type Error = Error;
 
-    fn try_from(mut bytes: &[u8]) -> Result<Self> {
-        Self::read_bytes(&mut bytes)
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        let mut rest = bytes;
+        Self::read_bytes(&mut rest).map_err(|error| {
+            let offset = bytes.len() - rest.len();
+            trace::parse_failure("bytes", &error, bytes.len(), Some(offset));
+            error
+        })
     }
 }
 
@@ -406,6 +780,71 @@ mod tests {
         assert_eq!(cid, cid2);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_db_key() {
+        use super::Cid;
+        use multihash::Multihash;
+
+        let mut cids = [
+            Cid::<32>::new_v1(0x71, Multihash::wrap(0x12, &[1; 32]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x12, &[2; 32]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[3; 4]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[3; 3]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[]).unwrap()),
+            Cid::new_v1(0x0129, Multihash::wrap(0x12, &[0; 32]).unwrap()),
+            Cid::new_v0(Multihash::wrap(0x12, &[4; 32]).unwrap()).unwrap(),
+        ];
+        let mut keys: Vec<_> = cids.iter().map(Cid::to_db_key).collect();
+        cids.sort();
+        keys.sort();
+        for (cid, key) in cids.iter().zip(&keys) {
+            assert_eq!(key.len(), Cid::<32>::DB_KEY_LEN);
+            assert_eq!(Cid::<32>::from_db_key(key).unwrap(), *cid);
+        }
+
+        let empty = Cid::<32>::new_v1(0x55, Multihash::wrap(0x00, &[]).unwrap());
+        let mut key = empty.to_db_key();
+        assert!(Cid::<32>::from_db_key(&key[1..]).is_err());
+        // Non-zero padding.
+        key[Cid::<32>::DB_KEY_LEN - 1] = 1;
+        assert!(Cid::<32>::from_db_key(&key).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_block_key() {
+        use super::{Cid, DAG_PB};
+        use multihash::Multihash;
+
+        let hash = Multihash::<64>::wrap(0x12, &[1; 32]).unwrap();
+        let v0 = Cid::new_v0(hash).unwrap();
+        let v1 = Cid::new_v1(DAG_PB, hash);
+        assert_eq!(v0.to_block_key(), v1.to_block_key());
+        assert_eq!(v1.to_block_key(), hash.to_bytes());
+        assert_eq!(Cid::from_block_key(DAG_PB, &v0.to_block_key()).unwrap(), v1);
+        assert!(Cid::<64>::from_block_key(DAG_PB, &[0x12, 0x20, 1]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_string_exact_capacity() {
+        use super::Cid;
+        use multibase::Base;
+        use std::str::FromStr;
+
+        let cid =
+            Cid::<64>::from_str("bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4")
+                .unwrap();
+        // Base32 is streamed into a string of the exact size, nothing is reallocated.
+        for base in [Base::Base32Lower, Base::Base32Upper] {
+            let encoded = cid.to_string_of_base(base).unwrap();
+            assert_eq!(encoded.len(), encoded.capacity(), "{:?}", base);
+            assert_eq!(encoded, multibase::encode(base, cid.to_bytes()));
+        }
+        assert_eq!(cid.to_bytes().capacity(), cid.encoded_len());
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_debug_instance() {
@@ -0,0 +1,315 @@
+//! A compact, columnar container for CIDs.
+//!
+//! A `Vec<Cid>` allocates the maximum digest size for every CID. The [`CidVec`] instead stores
+//! the individual parts of the CIDs in separate contiguous arrays, the digests are packed without
+//! any padding.
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::iter::FromIterator;
+
+use multihash::Multihash;
+use unsigned_varint::decode as varint_decode;
+
+use crate::cid::{Cid, DAG_PB, SHA2_256};
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// A borrowed view of a CID.
+///
+/// It's ordered the same way as [`Cid`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CidRef<'a> {
+    version: Version,
+    codec: u64,
+    hash_code: u64,
+    digest: &'a [u8],
+}
+
+impl<'a> CidRef<'a> {
+    /// Returns the cid version.
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the cid codec.
+    pub const fn codec(&self) -> u64 {
+        self.codec
+    }
+
+    /// Returns the code of the multihash.
+    pub const fn hash_code(&self) -> u64 {
+        self.hash_code
+    }
+
+    /// Returns the digest of the multihash.
+    pub const fn digest(&self) -> &'a [u8] {
+        self.digest
+    }
+
+    /// Decodes a binary CID from the start of `bytes` without copying the digest.
+    ///
+    /// Returns the CID and the number of bytes it occupied.
+    pub fn decode(bytes: &'a [u8]) -> Result<(Self, usize)> {
+        // CIDv0 has the fixed `0x12 0x20` prefix
+        if bytes.starts_with(&[0x12, 0x20]) {
+            let digest = bytes.get(2..34).ok_or(Error::InputTooShort)?;
+            let cid = Self {
+                version: Version::V0,
+                codec: DAG_PB,
+                hash_code: SHA2_256,
+                digest,
+            };
+            return Ok((cid, 34));
+        }
+
+        let (version, rest) = varint_decode::u64(bytes)?;
+        let (codec, rest) = varint_decode::u64(rest)?;
+        if Version::try_from(version)? == Version::V0 {
+            return Err(Error::InvalidExplicitCidV0);
+        }
+        let (hash_code, rest) = varint_decode::u64(rest)?;
+        let (size, rest) = varint_decode::u8(rest)?;
+        let digest = rest.get(..usize::from(size)).ok_or(Error::InputTooShort)?;
+        let cid = Self {
+            version: Version::V1,
+            codec,
+            hash_code,
+            digest,
+        };
+        Ok((cid, bytes.len() - rest.len() + digest.len()))
+    }
+
+    /// Returns the same CID, but with the digest stored somewhere else.
+    #[cfg(feature = "bumpalo")]
+    pub(crate) fn with_digest<'b>(&self, digest: &'b [u8]) -> CidRef<'b> {
+        CidRef {
+            version: self.version,
+            codec: self.codec,
+            hash_code: self.hash_code,
+            digest,
+        }
+    }
+
+    /// Returns an owned CID.
+    ///
+    /// Errors if the digest doesn't fit into a multihash of size `S`.
+    pub fn to_cid<const S: usize>(&self) -> Result<Cid<S>> {
+        let hash = Multihash::wrap(self.hash_code, self.digest)?;
+        Cid::new(self.version, self.codec, hash)
+    }
+}
+
+impl<'a> PartialOrd for CidRef<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for CidRef<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.version
+            .cmp(&other.version)
+            .then_with(|| self.codec.cmp(&other.codec))
+            .then_with(|| self.hash_code.cmp(&other.hash_code))
+            .then_with(|| self.digest.len().cmp(&other.digest.len()))
+            .then_with(|| self.digest.cmp(other.digest))
+    }
+}
+
+impl<'a, const S: usize> From<&'a Cid<S>> for CidRef<'a> {
+    fn from(cid: &'a Cid<S>) -> Self {
+        Self {
+            version: cid.version(),
+            codec: cid.codec(),
+            hash_code: cid.hash().code(),
+            digest: cid.hash().digest(),
+        }
+    }
+}
+
+/// A growable list of CIDs, stored as a struct of arrays.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CidVec {
+    versions: Vec<Version>,
+    codecs: Vec<u64>,
+    hash_codes: Vec<u64>,
+    /// The end of each digest within `digests`.
+    digest_ends: Vec<usize>,
+    /// All digests, concatenated.
+    digests: Vec<u8>,
+}
+
+impl CidVec {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty list with space for `capacity` CIDs with digests of `digest_size` bytes.
+    pub fn with_capacity(capacity: usize, digest_size: usize) -> Self {
+        Self {
+            versions: Vec::with_capacity(capacity),
+            codecs: Vec::with_capacity(capacity),
+            hash_codes: Vec::with_capacity(capacity),
+            digest_ends: Vec::with_capacity(capacity),
+            digests: Vec::with_capacity(capacity * digest_size),
+        }
+    }
+
+    /// Returns the number of CIDs.
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// Returns `true` if the list contains no CIDs.
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Appends a CID.
+    pub fn push<'a, C: Into<CidRef<'a>>>(&mut self, cid: C) {
+        let cid = cid.into();
+        self.versions.push(cid.version);
+        self.codecs.push(cid.codec);
+        self.hash_codes.push(cid.hash_code);
+        self.digests.extend_from_slice(cid.digest);
+        self.digest_ends.push(self.digests.len());
+    }
+
+    /// Returns the CID at the given index.
+    pub fn get(&self, index: usize) -> Option<CidRef<'_>> {
+        let end = *self.digest_ends.get(index)?;
+        let start = match index {
+            0 => 0,
+            _ => self.digest_ends[index - 1],
+        };
+        Some(CidRef {
+            version: self.versions[index],
+            codec: self.codecs[index],
+            hash_code: self.hash_codes[index],
+            digest: &self.digests[start..end],
+        })
+    }
+
+    /// Removes all CIDs.
+    pub fn clear(&mut self) {
+        self.versions.clear();
+        self.codecs.clear();
+        self.hash_codes.clear();
+        self.digest_ends.clear();
+        self.digests.clear();
+    }
+
+    /// Returns an iterator over the CIDs.
+    pub fn iter(&self) -> CidVecIter<'_> {
+        CidVecIter {
+            vec: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, C: Into<CidRef<'a>>> Extend<C> for CidVec {
+    fn extend<I: IntoIterator<Item = C>>(&mut self, iter: I) {
+        for cid in iter {
+            self.push(cid);
+        }
+    }
+}
+
+impl<'a, C: Into<CidRef<'a>>> FromIterator<C> for CidVec {
+    fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<'a> IntoIterator for &'a CidVec {
+    type Item = CidRef<'a>;
+    type IntoIter = CidVecIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the CIDs of a [`CidVec`].
+#[derive(Clone, Debug)]
+pub struct CidVecIter<'a> {
+    vec: &'a CidVec,
+    index: usize,
+}
+
+impl<'a> Iterator for CidVecIter<'a> {
+    type Item = CidRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cid = self.vec.get(self.index)?;
+        self.index += 1;
+        Some(cid)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for CidVecIter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CidRef, CidVec};
+    use crate::Cid;
+    use multihash::Multihash;
+
+    #[test]
+    fn same_order_as_cid() {
+        // Shorter digests sort first, even if their bytes are greater.
+        let cids = [
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[1, 0]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[2]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[1]).unwrap()),
+            Cid::new_v1(0x55, Multihash::wrap(0x00, &[]).unwrap()),
+        ];
+        let mut sorted = cids;
+        sorted.sort();
+        let mut refs: Vec<CidRef> = cids.iter().map(CidRef::from).collect();
+        refs.sort();
+        let expected: Vec<CidRef> = sorted.iter().map(CidRef::from).collect();
+        assert_eq!(refs, expected);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let cids = [
+            Cid::new_v0(Multihash::wrap(0x12, &[1; 32]).unwrap()).unwrap(),
+            Cid::new_v1(0x55, Multihash::wrap(0x12, &[2; 32]).unwrap()),
+            Cid::new_v1(0x71, Multihash::wrap(0x00, &[3; 5]).unwrap()),
+            Cid::new_v1(0x71, Multihash::wrap(0x00, &[]).unwrap()),
+        ];
+        let vec: CidVec = cids.iter().collect();
+        assert_eq!(vec.len(), cids.len());
+        assert_eq!(vec.get(2), Some(CidRef::from(&cids[2])));
+        assert_eq!(vec.get(4), None);
+        for (cid_ref, cid) in vec.iter().zip(cids.iter()) {
+            assert_eq!(cid_ref.to_cid::<64>().unwrap(), *cid);
+        }
+        assert!(vec.get(0).unwrap().to_cid::<16>().is_err());
+    }
+
+    #[test]
+    fn decode() {
+        let cid = Cid::new_v1(0x71, Multihash::wrap(0x12, &[3; 32]).unwrap());
+        let mut bytes = cid.to_bytes();
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let (cid_ref, len) = CidRef::decode(&bytes).unwrap();
+        assert_eq!(cid_ref, CidRef::from(&cid));
+        assert_eq!(len, cid.encoded_len());
+        assert!(CidRef::decode(&bytes[..len - 1]).is_err());
+    }
+}
@@ -0,0 +1,446 @@
+//! Collections that are keyed by CIDs.
+//!
+//! They use the [`CidHasher`], which uses the CID digest directly instead of hashing it again. If
+//! all CIDs use 32 bytes digests, use a digest size of 32 (e.g. `CidSet<32>`), which saves 32
+//! bytes per entry compared to the default size. The version, codec and multihash code take the
+//! same space either way.
+//!
+//! [`CidHasher`]: crate::hasher::CidHasher
+use std::collections::hash_map::{self, HashMap};
+use std::collections::hash_set::{self, HashSet};
+
+use crate::cid::Cid;
+use crate::hasher::BuildCidHasher;
+
+/// The maximum number of entries reserved upfront when deserializing, as the length in the input
+/// can't be trusted.
+#[cfg(feature = "serde")]
+const MAX_PREALLOCATED: usize = 4096;
+
+/// A set of CIDs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CidSet<const S: usize = 64> {
+    inner: HashSet<Cid<S>, BuildCidHasher>,
+}
+
+impl<const S: usize> CidSet<S> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty set with space for at least `capacity` CIDs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: HashSet::with_capacity_and_hasher(capacity, BuildCidHasher::default()),
+        }
+    }
+
+    /// Returns the number of CIDs in the set.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the set contains no CIDs.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Adds a CID to the set, returns whether it was newly inserted.
+    pub fn insert(&mut self, cid: Cid<S>) -> bool {
+        self.inner.insert(cid)
+    }
+
+    /// Returns `true` if the set contains the CID.
+    pub fn contains(&self, cid: &Cid<S>) -> bool {
+        self.inner.contains(cid)
+    }
+
+    /// Removes a CID from the set, returns whether it was present.
+    pub fn remove(&mut self, cid: &Cid<S>) -> bool {
+        self.inner.remove(cid)
+    }
+
+    /// Retains only the CIDs for which the predicate returns `true`.
+    pub fn retain<F: FnMut(&Cid<S>) -> bool>(&mut self, f: F) {
+        self.inner.retain(f)
+    }
+
+    /// Removes all CIDs.
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Returns an iterator over the CIDs, in arbitrary order.
+    pub fn iter(&self) -> hash_set::Iter<'_, Cid<S>> {
+        self.inner.iter()
+    }
+
+    /// Returns the CIDs that are in `self` or in `other`, without duplicates.
+    pub fn union<'a>(&'a self, other: &'a Self) -> hash_set::Union<'a, Cid<S>, BuildCidHasher> {
+        self.inner.union(&other.inner)
+    }
+
+    /// Returns the CIDs that are both in `self` and in `other`.
+    pub fn intersection<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> hash_set::Intersection<'a, Cid<S>, BuildCidHasher> {
+        self.inner.intersection(&other.inner)
+    }
+
+    /// Returns the CIDs that are in `self` but not in `other`.
+    pub fn difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> hash_set::Difference<'a, Cid<S>, BuildCidHasher> {
+        self.inner.difference(&other.inner)
+    }
+
+    /// Returns `true` if all CIDs of `self` are also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.inner.is_subset(&other.inner)
+    }
+}
+
+impl<const S: usize> Extend<Cid<S>> for CidSet<S> {
+    fn extend<I: IntoIterator<Item = Cid<S>>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+impl<'a, const S: usize> Extend<&'a Cid<S>> for CidSet<S> {
+    fn extend<I: IntoIterator<Item = &'a Cid<S>>>(&mut self, iter: I) {
+        self.inner.extend(iter.into_iter().copied())
+    }
+}
+
+impl<const S: usize> FromIterator<Cid<S>> for CidSet<S> {
+    fn from_iter<I: IntoIterator<Item = Cid<S>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const S: usize> IntoIterator for CidSet<S> {
+    type Item = Cid<S>;
+    type IntoIter = hash_set::IntoIter<Cid<S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, const S: usize> IntoIterator for &'a CidSet<S> {
+    type Item = &'a Cid<S>;
+    type IntoIter = hash_set::Iter<'a, Cid<S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A set is serialized as a sequence of CIDs.
+#[cfg(feature = "serde")]
+impl<const S: usize> serde::Serialize for CidSet<S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const S: usize> serde::Deserialize<'de> for CidSet<S> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SetVisitor<const S: usize>;
+
+        impl<'de, const S: usize> serde::de::Visitor<'de> for SetVisitor<S> {
+            type Value = CidSet<S>;
+
+            fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(fmt, "a sequence of CIDs")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut set =
+                    CidSet::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+                while let Some(cid) = seq.next_element()? {
+                    set.insert(cid);
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(SetVisitor)
+    }
+}
+
+/// A map keyed by CIDs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CidMap<V, const S: usize = 64> {
+    inner: HashMap<Cid<S>, V, BuildCidHasher>,
+}
+
+impl<V, const S: usize> Default for CidMap<V, S> {
+    fn default() -> Self {
+        Self {
+            inner: HashMap::default(),
+        }
+    }
+}
+
+impl<V, const S: usize> CidMap<V, S> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty map with space for at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: HashMap::with_capacity_and_hasher(capacity, BuildCidHasher::default()),
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Inserts a value, returns the previous value of that CID.
+    pub fn insert(&mut self, cid: Cid<S>, value: V) -> Option<V> {
+        self.inner.insert(cid, value)
+    }
+
+    /// Returns the value of a CID.
+    pub fn get(&self, cid: &Cid<S>) -> Option<&V> {
+        self.inner.get(cid)
+    }
+
+    /// Returns the mutable value of a CID.
+    pub fn get_mut(&mut self, cid: &Cid<S>) -> Option<&mut V> {
+        self.inner.get_mut(cid)
+    }
+
+    /// Returns `true` if the map contains the CID.
+    pub fn contains_key(&self, cid: &Cid<S>) -> bool {
+        self.inner.contains_key(cid)
+    }
+
+    /// Removes a CID from the map, returns its value.
+    pub fn remove(&mut self, cid: &Cid<S>) -> Option<V> {
+        self.inner.remove(cid)
+    }
+
+    /// Returns the entry of a CID for in-place manipulation.
+    pub fn entry(&mut self, cid: Cid<S>) -> hash_map::Entry<'_, Cid<S>, V> {
+        self.inner.entry(cid)
+    }
+
+    /// Retains only the entries for which the predicate returns `true`.
+    pub fn retain<F: FnMut(&Cid<S>, &mut V) -> bool>(&mut self, f: F) {
+        self.inner.retain(f)
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Returns an iterator over the entries, in arbitrary order.
+    pub fn iter(&self) -> hash_map::Iter<'_, Cid<S>, V> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator over the entries with mutable values, in arbitrary order.
+    pub fn iter_mut(&mut self) -> hash_map::IterMut<'_, Cid<S>, V> {
+        self.inner.iter_mut()
+    }
+
+    /// Returns an iterator over the CIDs, in arbitrary order.
+    pub fn keys(&self) -> hash_map::Keys<'_, Cid<S>, V> {
+        self.inner.keys()
+    }
+
+    /// Returns an iterator over the values, in arbitrary order.
+    pub fn values(&self) -> hash_map::Values<'_, Cid<S>, V> {
+        self.inner.values()
+    }
+}
+
+impl<V, const S: usize> Extend<(Cid<S>, V)> for CidMap<V, S> {
+    fn extend<I: IntoIterator<Item = (Cid<S>, V)>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+impl<V, const S: usize> FromIterator<(Cid<S>, V)> for CidMap<V, S> {
+    fn from_iter<I: IntoIterator<Item = (Cid<S>, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V, const S: usize> IntoIterator for CidMap<V, S> {
+    type Item = (Cid<S>, V);
+    type IntoIter = hash_map::IntoIter<Cid<S>, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, V, const S: usize> IntoIterator for &'a CidMap<V, S> {
+    type Item = (&'a Cid<S>, &'a V);
+    type IntoIter = hash_map::Iter<'a, Cid<S>, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A map is serialized as a sequence of `(cid, value)` tuples, as many formats only support
+/// strings as map keys.
+#[cfg(feature = "serde")]
+impl<V: serde::Serialize, const S: usize> serde::Serialize for CidMap<V, S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V: serde::Deserialize<'de>, const S: usize> serde::Deserialize<'de> for CidMap<V, S> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct MapVisitor<V, const S: usize>(core::marker::PhantomData<V>);
+
+        impl<'de, V: serde::Deserialize<'de>, const S: usize> serde::de::Visitor<'de> for MapVisitor<V, S> {
+            type Value = CidMap<V, S>;
+
+            fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(fmt, "a sequence of CID and value tuples")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut map =
+                    CidMap::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+                while let Some((cid, value)) = seq.next_element()? {
+                    map.insert(cid, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_seq(MapVisitor(core::marker::PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::{CidMap, CidSet};
+    use crate::CidGeneric;
+
+    fn cid(byte: u8) -> CidGeneric<32> {
+        CidGeneric::new_v1(0x55, Multihash::wrap(0x12, &[byte; 32]).unwrap())
+    }
+
+    /// A sequence that claims to have far more elements than it has.
+    #[cfg(feature = "serde")]
+    fn lying_seq() -> serde::de::value::SeqDeserializer<LyingIter, serde::de::value::Error> {
+        serde::de::value::SeqDeserializer::new(LyingIter)
+    }
+
+    #[cfg(feature = "serde")]
+    struct LyingIter;
+
+    #[cfg(feature = "serde")]
+    impl Iterator for LyingIter {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            None
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX, Some(usize::MAX))
+        }
+    }
+
+    #[test]
+    fn set_ops() {
+        let a: CidSet<32> = (0..4).map(cid).collect();
+        let b: CidSet<32> = (2..6).map(cid).collect();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(&cid(0)));
+        assert!(!a.contains(&cid(5)));
+        assert_eq!(a.union(&b).count(), 6);
+
+        let intersection: CidSet<32> = a.intersection(&b).copied().collect();
+        assert_eq!(intersection, (2..4).map(cid).collect());
+        assert!(intersection.is_subset(&a));
+        assert_eq!(a.difference(&b).count(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let set: CidSet<32> = (0..4).map(cid).collect();
+        let json = serde_json::to_string(&set).unwrap();
+        let set2: CidSet<32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(set, set2);
+
+        let empty: CidSet<32> = serde::Deserialize::deserialize(lying_seq()).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn map_entries() {
+        let mut refcounts: CidMap<u32, 32> = CidMap::new();
+        for byte in [0, 1, 1, 2, 2, 2] {
+            *refcounts.entry(cid(byte)).or_default() += 1;
+        }
+        assert_eq!(refcounts.len(), 3);
+        assert_eq!(refcounts.get(&cid(2)), Some(&3));
+
+        refcounts.retain(|_, count| *count > 1);
+        assert_eq!(refcounts.len(), 2);
+        assert!(!refcounts.contains_key(&cid(0)));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn map_serde() {
+        let map: CidMap<u64, 32> = (0..4).map(|byte| (cid(byte), u64::from(byte))).collect();
+        let json = serde_json::to_string(&map).unwrap();
+        let map2: CidMap<u64, 32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map, map2);
+
+        let empty: CidMap<u64, 32> = serde::Deserialize::deserialize(lying_seq()).unwrap();
+        assert!(empty.is_empty());
+    }
+}
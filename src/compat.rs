@@ -0,0 +1,73 @@
+//! Interoperability with older versions of this crate.
+//!
+//! Dependency trees often contain several major versions of this crate at once. Their CID types
+//! are distinct, but all share the same binary representation, which [`ConvertViaBytes`] uses to
+//! convert between them. Conversions with a specific older version are enabled with the
+//! `compat-0-8`, `compat-0-9` and `compat-0-10` features.
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::cid::Cid;
+use crate::error::Result;
+
+/// Conversion into a CID type of another version of this crate, via the binary representation.
+pub trait ConvertViaBytes<T> {
+    /// Converts the CID, fails if the target version rejects it, e.g. as the digest is too big.
+    fn convert_via_bytes(&self) -> Result<T>;
+}
+
+/// Converts the binary representation of a CID into this version's CID.
+///
+/// Works with any CID type that can return its bytes, also of versions without a compat feature.
+pub fn from_bytes_of<const S: usize>(bytes: Vec<u8>) -> Result<Cid<S>> {
+    Cid::try_from(bytes)
+}
+
+macro_rules! compat {
+    ($feature:literal, $krate:ident) => {
+        #[cfg(feature = $feature)]
+        impl<const A: usize, const B: usize> ConvertViaBytes<$krate::CidGeneric<B>> for Cid<A> {
+            fn convert_via_bytes(&self) -> Result<$krate::CidGeneric<B>> {
+                $krate::CidGeneric::try_from(self.to_bytes())
+                    .map_err(|_| crate::Error::ParsingError)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl<const A: usize, const B: usize> ConvertViaBytes<Cid<B>> for $krate::CidGeneric<A> {
+            fn convert_via_bytes(&self) -> Result<Cid<B>> {
+                from_bytes_of(self.to_bytes())
+            }
+        }
+    };
+}
+
+compat!("compat-0-8", cid_0_8);
+compat!("compat-0-9", cid_0_9);
+compat!("compat-0-10", cid_0_10);
+
+#[cfg(all(test, feature = "compat-0-8", feature = "compat-0-10"))]
+mod tests {
+    use super::ConvertViaBytes;
+    use crate::{Cid, CidGeneric};
+
+    #[test]
+    fn roundtrip() {
+        let cid: Cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+            .parse()
+            .unwrap();
+        let old: cid_0_8::Cid = cid.convert_via_bytes().unwrap();
+        assert_eq!(old.to_string(), cid.to_string());
+        let back: Cid = old.convert_via_bytes().unwrap();
+        assert_eq!(back, cid);
+
+        let newer: cid_0_10::Cid = cid.convert_via_bytes().unwrap();
+        assert_eq!(newer.to_bytes(), cid.to_bytes());
+        let small: Result<cid_0_10::CidGeneric<16>, _> = cid.convert_via_bytes();
+        assert!(small.is_err());
+        let small: Result<CidGeneric<16>, _> = old.convert_via_bytes();
+        assert!(small.is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! [Diesel] support.
+//!
+//! CIDs can be used with `Binary` columns (the binary representation) and `Text` columns (the
+//! string representation). Reading works with every backend, writing is implemented for the
+//! backends enabled via the `diesel-postgres` and `diesel-sqlite` features. When reading, the
+//! column must contain exactly the CID, nothing before or after it.
+//!
+//! [Diesel]: https://diesel.rs
+use std::convert::TryFrom;
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+use diesel::sql_types::{Binary, Text};
+
+use crate::cid::Cid;
+use crate::error::Error;
+
+impl<DB: Backend, const S: usize> FromSql<Binary, DB> for Cid<S>
+where
+    Vec<u8>: FromSql<Binary, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let bytes = <Vec<u8> as FromSql<Binary, DB>>::from_sql(bytes)?;
+        let cid = Cid::try_from(bytes.as_slice())?;
+        if cid.encoded_len() != bytes.len() {
+            return Err(Error::ParsingError.into());
+        }
+        Ok(cid)
+    }
+}
+
+impl<DB: Backend, const S: usize> FromSql<Text, DB> for Cid<S>
+where
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let s = <String as FromSql<Text, DB>>::from_sql(bytes)?;
+        Ok(Cid::parse_strict(&s)?)
+    }
+}
+
+#[cfg(feature = "diesel-postgres")]
+mod postgres {
+    use std::io::Write;
+
+    use diesel::pg::Pg;
+    use diesel::serialize::{self, IsNull, Output, ToSql};
+    use diesel::sql_types::{Binary, Text};
+
+    use crate::cid::Cid;
+
+    impl<const S: usize> ToSql<Binary, Pg> for Cid<S> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+            self.write_bytes(&mut *out)?;
+            Ok(IsNull::No)
+        }
+    }
+
+    impl<const S: usize> ToSql<Text, Pg> for Cid<S> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+            write!(out, "{}", self)?;
+            Ok(IsNull::No)
+        }
+    }
+}
+
+#[cfg(feature = "diesel-sqlite")]
+mod sqlite {
+    use diesel::serialize::{self, IsNull, Output, ToSql};
+    use diesel::sql_types::{Binary, Text};
+    use diesel::sqlite::Sqlite;
+
+    use crate::cid::Cid;
+
+    impl<const S: usize> ToSql<Binary, Sqlite> for Cid<S> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.to_bytes());
+            Ok(IsNull::No)
+        }
+    }
+
+    impl<const S: usize> ToSql<Text, Sqlite> for Cid<S> {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.to_string());
+            Ok(IsNull::No)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "diesel-sqlite"))]
+mod tests {
+    use diesel::dsl::sql;
+    use diesel::prelude::*;
+    use diesel::sql_types::{Binary, Text};
+    use multihash::Multihash;
+
+    use crate::Cid;
+
+    #[test]
+    fn sqlite_roundtrip() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        let cid = Cid::new_v1(0x55, Multihash::wrap(0x12, &[1; 32]).unwrap());
+
+        let bytes: Vec<u8> = diesel::select(cid.into_sql::<Binary>())
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(bytes, cid.to_bytes());
+        let binary: Cid = diesel::select(cid.into_sql::<Binary>())
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(binary, cid);
+
+        let text: Cid = diesel::select(cid.into_sql::<Text>())
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(text, cid);
+
+        let invalid = diesel::select(sql::<Text>("'foo'")).get_result::<Cid>(&mut conn);
+        assert!(invalid.is_err());
+
+        // Only exactly the CID is accepted, without trailing bytes or a path around it.
+        let exact = format!("X'{}'", hex(&cid.to_bytes()));
+        let exact = diesel::select(sql::<Binary>(&exact)).get_result::<Cid>(&mut conn);
+        assert_eq!(exact.unwrap(), cid);
+        let trailing = format!("X'{}00'", hex(&cid.to_bytes()));
+        let trailing = diesel::select(sql::<Binary>(&trailing)).get_result::<Cid>(&mut conn);
+        assert!(trailing.is_err());
+        let path = format!("'/ipfs/{}'", cid);
+        let path = diesel::select(sql::<Text>(&path)).get_result::<Cid>(&mut conn);
+        assert!(path.is_err());
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
@@ -0,0 +1,100 @@
+//! [EIP-1577] contenthash records, as used by ENS.
+//!
+//! A contenthash is the binary representation of a CIDv1 prefixed with the varint of a namespace
+//! multicodec: `ipns-ns` for CIDs with the `libp2p-key` codec, `ipfs-ns` for everything else.
+//!
+//! [EIP-1577]: https://eips.ethereum.org/EIPS/eip-1577
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use unsigned_varint::{decode as varint_decode, encode as varint_encode};
+
+use crate::cid::{Cid, LIBP2P_KEY};
+use crate::error::{Error, Result};
+
+/// The `ipfs-ns` multicodec.
+pub const IPFS_NS: u64 = 0xe3;
+/// The `ipns-ns` multicodec.
+pub const IPNS_NS: u64 = 0xe5;
+
+impl<const S: usize> Cid<S> {
+    /// Returns the EIP-1577 contenthash of the CID.
+    ///
+    /// CIDv0 are converted to CIDv1 first, as expected by ENS resolvers.
+    pub fn to_contenthash(&self) -> Result<Vec<u8>> {
+        let cid = self.into_v1()?;
+        let namespace = if cid.codec() == LIBP2P_KEY {
+            IPNS_NS
+        } else {
+            IPFS_NS
+        };
+        let mut namespace_buf = varint_encode::u64_buffer();
+        let namespace = varint_encode::u64(namespace, &mut namespace_buf);
+
+        let mut bytes = Vec::with_capacity(namespace.len() + cid.encoded_len());
+        bytes.extend_from_slice(namespace);
+        cid.write_bytes(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Parses an EIP-1577 contenthash with the `ipfs-ns` or `ipns-ns` namespace.
+    ///
+    /// Other namespaces, e.g. Swarm, result in [`Error::UnknownCodec`].
+    pub fn from_contenthash(bytes: &[u8]) -> Result<Self> {
+        let (namespace, mut bytes) =
+            varint_decode::u64(bytes).map_err(|_| Error::VarIntDecodeError)?;
+        if namespace != IPFS_NS && namespace != IPNS_NS {
+            return Err(Error::UnknownCodec);
+        }
+        let cid = Self::read_bytes(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(Error::ParsingError);
+        }
+        Ok(cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cid, Error};
+
+    #[test]
+    fn contenthash() {
+        // From EIP-1577.
+        let ipfs = "e3010170122029f2d17be6139079dc48696d1f582a8530eb9805b561eda517e22a892c7e3f1f";
+        let ipfs = multibase::Base::Base16Lower.decode(ipfs).unwrap();
+        let cid = Cid::from_contenthash(&ipfs).unwrap();
+        assert_eq!(
+            cid.to_string(),
+            "bafybeibj6lixxzqtsb45ysdjnupvqkufgdvzqbnvmhw2kf7cfkesy7r7d4"
+        );
+        assert_eq!(cid.to_contenthash().unwrap(), ipfs);
+
+        let v0 = Cid::try_from("QmRAQB6YaCyidP37UdDnjFY5vQuiBrcqdyoW1CuDgwxkD4").unwrap();
+        assert_eq!(v0.to_contenthash().unwrap(), ipfs);
+
+        let ipns = "e5010172002408011220".to_string() + &"00".repeat(32);
+        let ipns = multibase::Base::Base16Lower.decode(ipns).unwrap();
+        let cid = Cid::from_contenthash(&ipns).unwrap();
+        assert_eq!(cid.codec(), 0x72);
+        assert_eq!(cid.to_contenthash().unwrap(), ipns);
+    }
+
+    #[test]
+    fn invalid() {
+        let swarm = [0xe4, 0x01, 0x01, 0xfa, 0x01, 0x1b, 0x20];
+        assert!(matches!(
+            Cid::from_contenthash(&swarm),
+            Err(Error::UnknownCodec)
+        ));
+        assert!(Cid::from_contenthash(&[0xe3]).is_err());
+
+        let mut trailing = Cid::default().to_contenthash().unwrap();
+        trailing.push(0);
+        assert!(matches!(
+            Cid::from_contenthash(&trailing),
+            Err(Error::ParsingError)
+        ));
+    }
+}
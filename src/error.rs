@@ -32,6 +32,12 @@ pub enum Error {
     Io(io::Error),
     /// Invalid explicit CIDv0.
     InvalidExplicitCidV0,
+    /// The CID doesn't fit into a DNS label.
+    DnsLabelTooLong,
+    /// Valid CID that isn't in its canonical form.
+    NotCanonical,
+    /// A required field of a [`CidBuilder`](crate::CidBuilder) isn't set, the name of the field.
+    MissingBuilderField(&'static str),
 }
 
 #[cfg(feature = "std")]
@@ -51,6 +57,9 @@ impl fmt::Display for Error {
             VarIntDecodeError => "Failed to decode unsigned varint format",
             Io(err) => return write!(f, "{}", err),
             InvalidExplicitCidV0 => "CIDv0 cannot be specified in CIDv1 format",
+            DnsLabelTooLong => "CID is too long for a DNS label",
+            NotCanonical => "CID is not in its canonical form",
+            MissingBuilderField(field) => return write!(f, "CID builder is missing the {}", field),
         };
 
         f.write_str(error)
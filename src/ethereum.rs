@@ -0,0 +1,192 @@
+//! Ethereum CIDs.
+//!
+//! Ethereum objects are identified by the Keccak-256 hash of their RLP encoding, as CIDv1 with the
+//! `keccak-256` multihash and a codec per object type, e.g. `eth-block` for block headers or
+//! `eth-state-trie` for the nodes of the state trie, whose root is the block's state root.
+use core::convert::TryInto;
+
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// The `eth-block` multicodec.
+pub const ETH_BLOCK: u64 = 0x90;
+/// The `eth-tx-trie` multicodec.
+pub const ETH_TX_TRIE: u64 = 0x92;
+/// The `eth-tx` multicodec.
+pub const ETH_TX: u64 = 0x93;
+/// The `eth-tx-receipt-trie` multicodec.
+pub const ETH_TX_RECEIPT_TRIE: u64 = 0x94;
+/// The `eth-tx-receipt` multicodec.
+pub const ETH_TX_RECEIPT: u64 = 0x95;
+/// The `eth-state-trie` multicodec.
+pub const ETH_STATE_TRIE: u64 = 0x96;
+/// The `eth-account-snapshot` multicodec.
+pub const ETH_ACCOUNT_SNAPSHOT: u64 = 0x97;
+/// The `eth-storage-trie` multicodec.
+pub const ETH_STORAGE_TRIE: u64 = 0x98;
+/// The `keccak-256` multihash code.
+pub const KECCAK_256: u64 = 0x1b;
+
+/// The size of a Keccak-256 hash.
+pub const HASH_LEN: usize = 32;
+
+/// A Keccak-256 hash, as stored in a CID.
+pub type Hash = [u8; HASH_LEN];
+
+/// What an Ethereum CID points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EthKind {
+    /// A block header.
+    Block,
+    /// A node of a transaction trie.
+    TxTrie,
+    /// A transaction.
+    Tx,
+    /// A node of a receipt trie.
+    TxReceiptTrie,
+    /// A transaction receipt.
+    TxReceipt,
+    /// A node of the state trie.
+    StateTrie,
+    /// An account, i.e. a leaf of the state trie.
+    AccountSnapshot,
+    /// A node of a storage trie.
+    StorageTrie,
+}
+
+impl EthKind {
+    /// Returns the codec of the kind.
+    pub const fn codec(self) -> u64 {
+        match self {
+            Self::Block => ETH_BLOCK,
+            Self::TxTrie => ETH_TX_TRIE,
+            Self::Tx => ETH_TX,
+            Self::TxReceiptTrie => ETH_TX_RECEIPT_TRIE,
+            Self::TxReceipt => ETH_TX_RECEIPT,
+            Self::StateTrie => ETH_STATE_TRIE,
+            Self::AccountSnapshot => ETH_ACCOUNT_SNAPSHOT,
+            Self::StorageTrie => ETH_STORAGE_TRIE,
+        }
+    }
+
+    fn from_codec(codec: u64) -> Result<Self> {
+        match codec {
+            ETH_BLOCK => Ok(Self::Block),
+            ETH_TX_TRIE => Ok(Self::TxTrie),
+            ETH_TX => Ok(Self::Tx),
+            ETH_TX_RECEIPT_TRIE => Ok(Self::TxReceiptTrie),
+            ETH_TX_RECEIPT => Ok(Self::TxReceipt),
+            ETH_STATE_TRIE => Ok(Self::StateTrie),
+            ETH_ACCOUNT_SNAPSHOT => Ok(Self::AccountSnapshot),
+            ETH_STORAGE_TRIE => Ok(Self::StorageTrie),
+            _ => Err(Error::UnknownCodec),
+        }
+    }
+}
+
+/// Creates a CID from a Keccak-256 hash.
+///
+/// Fails if the hash isn't 32 bytes or if `S` is smaller than 32.
+pub fn hash_to_cid<const S: usize>(kind: EthKind, hash: &[u8]) -> Result<Cid<S>> {
+    let hash: Hash = hash.try_into().map_err(|_| Error::ParsingError)?;
+    Ok(Cid::new_v1(
+        kind.codec(),
+        Multihash::wrap(KECCAK_256, &hash)?,
+    ))
+}
+
+/// Returns the kind and the Keccak-256 hash of an Ethereum CID.
+pub fn cid_to_hash<const S: usize>(cid: &Cid<S>) -> Result<(EthKind, Hash)> {
+    if cid.version() != Version::V1 {
+        return Err(Error::InvalidCidVersion);
+    }
+    let kind = EthKind::from_codec(cid.codec())?;
+    if cid.hash().code() != KECCAK_256 {
+        return Err(Error::ParsingError);
+    }
+    let hash = cid
+        .hash()
+        .digest()
+        .try_into()
+        .map_err(|_| Error::ParsingError)?;
+    Ok((kind, hash))
+}
+
+/// Returns the hash of a CID of the given kind.
+fn hash_of<const S: usize>(kind: EthKind, cid: &Cid<S>) -> Result<Hash> {
+    match cid_to_hash(cid)? {
+        (found, hash) if found == kind => Ok(hash),
+        _ => Err(Error::UnknownCodec),
+    }
+}
+
+/// Creates the CID of a block from its hash.
+pub fn block_hash_to_cid<const S: usize>(hash: &[u8]) -> Result<Cid<S>> {
+    hash_to_cid(EthKind::Block, hash)
+}
+
+/// Creates the CID of a transaction from its hash.
+pub fn tx_hash_to_cid<const S: usize>(hash: &[u8]) -> Result<Cid<S>> {
+    hash_to_cid(EthKind::Tx, hash)
+}
+
+/// Creates the CID of the root node of the state trie from a block's state root.
+pub fn state_root_to_cid<const S: usize>(hash: &[u8]) -> Result<Cid<S>> {
+    hash_to_cid(EthKind::StateTrie, hash)
+}
+
+/// Returns the block hash of a block CID.
+pub fn cid_to_block_hash<const S: usize>(cid: &Cid<S>) -> Result<Hash> {
+    hash_of(EthKind::Block, cid)
+}
+
+/// Returns the transaction hash of a transaction CID.
+pub fn cid_to_tx_hash<const S: usize>(cid: &Cid<S>) -> Result<Hash> {
+    hash_of(EthKind::Tx, cid)
+}
+
+/// Returns the hash of a state trie node CID.
+pub fn cid_to_state_root<const S: usize>(cid: &Cid<S>) -> Result<Hash> {
+    hash_of(EthKind::StateTrie, cid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let hash = [0xd4; HASH_LEN];
+        let block: Cid<64> = block_hash_to_cid(&hash).unwrap();
+        assert_eq!(block.codec(), ETH_BLOCK);
+        assert_eq!(block.hash().code(), KECCAK_256);
+        assert_eq!(cid_to_block_hash(&block).unwrap(), hash);
+        assert!(cid_to_tx_hash(&block).is_err());
+
+        let state: Cid<64> = state_root_to_cid(&hash).unwrap();
+        assert_eq!(cid_to_state_root(&state).unwrap(), hash);
+        let tx: Cid<64> = tx_hash_to_cid(&hash).unwrap();
+        assert_eq!(cid_to_hash(&tx).unwrap(), (EthKind::Tx, hash));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(block_hash_to_cid::<64>(&[0; 20]).is_err());
+        assert!(block_hash_to_cid::<16>(&[0; 32]).is_err());
+
+        let sha2 = Multihash::<64>::wrap(0x12, &[0; 32]).unwrap();
+        assert!(matches!(
+            cid_to_hash(&Cid::new_v1(ETH_BLOCK, sha2)),
+            Err(Error::ParsingError)
+        ));
+        assert!(matches!(
+            cid_to_hash(&Cid::new_v1(0x55, sha2)),
+            Err(Error::UnknownCodec)
+        ));
+        let short = Multihash::<64>::wrap(KECCAK_256, &[0; 16]).unwrap();
+        assert!(cid_to_hash(&Cid::new_v1(ETH_TX, short)).is_err());
+    }
+}
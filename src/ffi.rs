@@ -0,0 +1,337 @@
+//! A C API.
+//!
+//! CIDs are passed by value as [`CCid`], a plain struct with a stable layout, so no memory is ever
+//! allocated across the boundary. Strings and bytes are written into caller provided buffers.
+//! All functions return [`CID_OK`] on success or one of the negative `CID_ERR_*` codes.
+//!
+//! A header can be generated with `cbindgen --config cbindgen.toml --output cid.h`.
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::{ptr, slice};
+
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// The maximum digest size of a [`CCid`].
+pub const CID_MAX_DIGEST_LEN: usize = 64;
+
+/// Success.
+pub const CID_OK: i32 = 0;
+/// A pointer argument was null.
+pub const CID_ERR_NULL: i32 = -1;
+/// The output buffer is too small, the needed size was written to `written`.
+pub const CID_ERR_BUFFER_TOO_SMALL: i32 = -2;
+/// The input string is not valid UTF-8.
+pub const CID_ERR_INVALID_UTF8: i32 = -3;
+/// See [`Error::UnknownCodec`].
+pub const CID_ERR_UNKNOWN_CODEC: i32 = -10;
+/// See [`Error::InputTooShort`].
+pub const CID_ERR_INPUT_TOO_SHORT: i32 = -11;
+/// See [`Error::ParsingError`].
+pub const CID_ERR_PARSING: i32 = -12;
+/// See [`Error::InvalidCidVersion`].
+pub const CID_ERR_INVALID_CID_VERSION: i32 = -13;
+/// See [`Error::InvalidCidV0Codec`].
+pub const CID_ERR_INVALID_CID_V0_CODEC: i32 = -14;
+/// See [`Error::InvalidCidV0Multihash`].
+pub const CID_ERR_INVALID_CID_V0_MULTIHASH: i32 = -15;
+/// See [`Error::InvalidCidV0Base`].
+pub const CID_ERR_INVALID_CID_V0_BASE: i32 = -16;
+/// See [`Error::VarIntDecodeError`].
+pub const CID_ERR_VARINT_DECODE: i32 = -17;
+/// See [`Error::Io`].
+pub const CID_ERR_IO: i32 = -18;
+/// See [`Error::InvalidExplicitCidV0`].
+pub const CID_ERR_INVALID_EXPLICIT_CID_V0: i32 = -19;
+/// See [`Error::DnsLabelTooLong`].
+pub const CID_ERR_DNS_LABEL_TOO_LONG: i32 = -20;
+/// See [`Error::NotCanonical`].
+pub const CID_ERR_NOT_CANONICAL: i32 = -21;
+/// See [`Error::MissingBuilderField`].
+pub const CID_ERR_MISSING_BUILDER_FIELD: i32 = -22;
+
+/// A CID with a stable memory layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CCid {
+    version: u64,
+    codec: u64,
+    hash_code: u64,
+    digest_len: usize,
+    digest: [u8; CID_MAX_DIGEST_LEN],
+}
+
+impl<const S: usize> From<&Cid<S>> for CCid {
+    /// Panics if the digest is bigger than [`CID_MAX_DIGEST_LEN`].
+    fn from(cid: &Cid<S>) -> Self {
+        let digest = cid.hash().digest();
+        let mut ccid = Self {
+            version: cid.version().into(),
+            codec: cid.codec(),
+            hash_code: cid.hash().code(),
+            digest_len: digest.len(),
+            digest: [0; CID_MAX_DIGEST_LEN],
+        };
+        ccid.digest[..digest.len()].copy_from_slice(digest);
+        ccid
+    }
+}
+
+impl<const S: usize> TryFrom<&CCid> for Cid<S> {
+    type Error = Error;
+
+    fn try_from(ccid: &CCid) -> Result<Self> {
+        let digest = ccid
+            .digest
+            .get(..ccid.digest_len)
+            .ok_or(Error::ParsingError)?;
+        let hash = Multihash::wrap(ccid.hash_code, digest)?;
+        Cid::new(Version::try_from(ccid.version)?, ccid.codec, hash)
+    }
+}
+
+fn error_code(error: &Error) -> i32 {
+    match error {
+        Error::UnknownCodec => CID_ERR_UNKNOWN_CODEC,
+        Error::InputTooShort => CID_ERR_INPUT_TOO_SHORT,
+        Error::ParsingError => CID_ERR_PARSING,
+        Error::InvalidCidVersion => CID_ERR_INVALID_CID_VERSION,
+        Error::InvalidCidV0Codec => CID_ERR_INVALID_CID_V0_CODEC,
+        Error::InvalidCidV0Multihash => CID_ERR_INVALID_CID_V0_MULTIHASH,
+        Error::InvalidCidV0Base => CID_ERR_INVALID_CID_V0_BASE,
+        Error::VarIntDecodeError => CID_ERR_VARINT_DECODE,
+        Error::Io(_) => CID_ERR_IO,
+        Error::InvalidExplicitCidV0 => CID_ERR_INVALID_EXPLICIT_CID_V0,
+        Error::DnsLabelTooLong => CID_ERR_DNS_LABEL_TOO_LONG,
+        Error::NotCanonical => CID_ERR_NOT_CANONICAL,
+        Error::MissingBuilderField(_) => CID_ERR_MISSING_BUILDER_FIELD,
+    }
+}
+
+fn store(result: Result<Cid<CID_MAX_DIGEST_LEN>>, out: &mut CCid) -> i32 {
+    match result {
+        Ok(cid) => {
+            *out = CCid::from(&cid);
+            CID_OK
+        }
+        Err(error) => error_code(&error),
+    }
+}
+
+/// Copies `bytes` into the buffer, followed by a NUL byte if `nul` is set.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `buf_len` bytes, `written` must be valid for writes.
+unsafe fn write_out(
+    bytes: &[u8],
+    nul: bool,
+    buf: *mut u8,
+    buf_len: usize,
+    written: *mut usize,
+) -> i32 {
+    if buf.is_null() || written.is_null() {
+        return CID_ERR_NULL;
+    }
+    *written = bytes.len();
+    if bytes.len() + usize::from(nul) > buf_len {
+        return CID_ERR_BUFFER_TOO_SMALL;
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+    if nul {
+        *buf.add(bytes.len()) = 0;
+    }
+    CID_OK
+}
+
+/// Parses a NUL-terminated string into `out`.
+///
+/// # Safety
+///
+/// `s` must be a valid NUL-terminated string, `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cid_from_str(s: *const c_char, out: *mut CCid) -> i32 {
+    if s.is_null() || out.is_null() {
+        return CID_ERR_NULL;
+    }
+    let s = match CStr::from_ptr(s).to_str() {
+        Ok(s) => s,
+        Err(_) => return CID_ERR_INVALID_UTF8,
+    };
+    store(Cid::try_from(s), &mut *out)
+}
+
+/// Parses `len` bytes of a binary CID into `out`.
+///
+/// # Safety
+///
+/// `bytes` must be valid for reads of `len` bytes, `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cid_from_bytes(bytes: *const u8, len: usize, out: *mut CCid) -> i32 {
+    if bytes.is_null() || out.is_null() {
+        return CID_ERR_NULL;
+    }
+    store(Cid::try_from(slice::from_raw_parts(bytes, len)), &mut *out)
+}
+
+/// Writes the NUL-terminated string representation of a CID into `buf`.
+///
+/// The length of the string (without the NUL byte) is written to `written`, also if the buffer is
+/// too small.
+///
+/// # Safety
+///
+/// `cid` must be valid for reads, `buf` must be valid for writes of `buf_len` bytes, `written`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cid_to_str(
+    cid: *const CCid,
+    buf: *mut c_char,
+    buf_len: usize,
+    written: *mut usize,
+) -> i32 {
+    if cid.is_null() {
+        return CID_ERR_NULL;
+    }
+    match Cid::<CID_MAX_DIGEST_LEN>::try_from(&*cid) {
+        Ok(cid) => write_out(
+            cid.to_string().as_bytes(),
+            true,
+            buf.cast(),
+            buf_len,
+            written,
+        ),
+        Err(error) => error_code(&error),
+    }
+}
+
+/// Writes the binary representation of a CID into `buf`.
+///
+/// The length is written to `written`, also if the buffer is too small.
+///
+/// # Safety
+///
+/// `cid` must be valid for reads, `buf` must be valid for writes of `buf_len` bytes, `written`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cid_to_bytes(
+    cid: *const CCid,
+    buf: *mut u8,
+    buf_len: usize,
+    written: *mut usize,
+) -> i32 {
+    if cid.is_null() {
+        return CID_ERR_NULL;
+    }
+    match Cid::<CID_MAX_DIGEST_LEN>::try_from(&*cid) {
+        Ok(cid) => write_out(&cid.to_bytes(), false, buf, buf_len, written),
+        Err(error) => error_code(&error),
+    }
+}
+
+/// Returns the version of a CID.
+///
+/// # Safety
+///
+/// `cid` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cid_version(cid: *const CCid) -> u64 {
+    (*cid).version
+}
+
+/// Returns the codec of a CID.
+///
+/// # Safety
+///
+/// `cid` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cid_codec(cid: *const CCid) -> u64 {
+    (*cid).codec
+}
+
+/// Returns the multihash code of a CID.
+///
+/// # Safety
+///
+/// `cid` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn cid_hash_code(cid: *const CCid) -> u64 {
+    (*cid).hash_code
+}
+
+/// Returns a pointer to the digest of a CID and writes its length to `len`.
+///
+/// The pointer is valid as long as `cid` is.
+///
+/// # Safety
+///
+/// `cid` and `len` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn cid_digest(cid: *const CCid, len: *mut usize) -> *const u8 {
+    *len = (*cid).digest_len;
+    (*cid).digest.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let s = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        let c_str = CString::new(s).unwrap();
+        let mut ccid = CCid::from(&Cid::<64>::default());
+        unsafe {
+            assert_eq!(cid_from_str(c_str.as_ptr(), &mut ccid), CID_OK);
+            assert_eq!(cid_version(&ccid), 1);
+            assert_eq!(cid_codec(&ccid), 0x55);
+            assert_eq!(cid_hash_code(&ccid), 0x12);
+            let mut len = 0;
+            let digest = cid_digest(&ccid, &mut len);
+            assert_eq!(len, 32);
+            assert_eq!(*digest, 0x2c);
+
+            let mut buf = [0u8; 100];
+            let mut written = 0;
+            assert_eq!(
+                cid_to_str(&ccid, buf.as_mut_ptr().cast(), buf.len(), &mut written),
+                CID_OK
+            );
+            assert_eq!(&buf[..written], s.as_bytes());
+            assert_eq!(buf[written], 0);
+            assert_eq!(
+                cid_to_str(&ccid, buf.as_mut_ptr().cast(), written, &mut written),
+                CID_ERR_BUFFER_TOO_SMALL
+            );
+            assert_eq!(written, s.len());
+
+            assert_eq!(
+                cid_to_bytes(&ccid, buf.as_mut_ptr(), buf.len(), &mut written),
+                CID_OK
+            );
+            let mut parsed = CCid::from(&Cid::<64>::default());
+            assert_eq!(cid_from_bytes(buf.as_ptr(), written, &mut parsed), CID_OK);
+            assert_eq!(parsed, ccid);
+            assert_eq!(
+                cid_from_bytes(buf.as_ptr(), written - 1, &mut parsed),
+                CID_ERR_PARSING
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut ccid = CCid::from(&Cid::<64>::default());
+        let c_str = CString::new("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zIII").unwrap();
+        unsafe {
+            assert_eq!(cid_from_str(ptr::null(), &mut ccid), CID_ERR_NULL);
+            assert_eq!(cid_from_str(c_str.as_ptr(), &mut ccid), CID_ERR_PARSING);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Filecoin commitment CIDs.
+//!
+//! Filecoin represents its 32 byte commitments as CIDv1:
+//!
+//! - unsealed commitments, i.e. piece (CommP) and data (CommD) commitments, use the
+//!   `fil-commitment-unsealed` codec and the `sha2-256-trunc254-padded` multihash,
+//! - sealed commitments, i.e. replica commitments (CommR), use the `fil-commitment-sealed` codec
+//!   and the `poseidon-bls12_381-a2-fc1` multihash.
+use core::convert::TryInto;
+
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// The `fil-commitment-unsealed` multicodec.
+pub const FIL_COMMITMENT_UNSEALED: u64 = 0xf101;
+/// The `fil-commitment-sealed` multicodec.
+pub const FIL_COMMITMENT_SEALED: u64 = 0xf102;
+/// The `sha2-256-trunc254-padded` multihash code.
+pub const SHA2_256_TRUNC254_PADDED: u64 = 0x1012;
+/// The `poseidon-bls12_381-a2-fc1` multihash code.
+pub const POSEIDON_BLS12_381_A2_FC1: u64 = 0xb401;
+
+/// The size of a commitment.
+pub const COMMITMENT_LEN: usize = 32;
+
+/// A commitment, as stored in a CID.
+pub type Commitment = [u8; COMMITMENT_LEN];
+
+/// The kind of a commitment CID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommitmentKind {
+    /// A piece (CommP) or data (CommD) commitment.
+    Unsealed,
+    /// A replica commitment (CommR).
+    Sealed,
+}
+
+impl CommitmentKind {
+    /// Returns the codec of the kind.
+    pub const fn codec(self) -> u64 {
+        match self {
+            Self::Unsealed => FIL_COMMITMENT_UNSEALED,
+            Self::Sealed => FIL_COMMITMENT_SEALED,
+        }
+    }
+
+    /// Returns the multihash code required for the kind.
+    pub const fn hash_code(self) -> u64 {
+        match self {
+            Self::Unsealed => SHA2_256_TRUNC254_PADDED,
+            Self::Sealed => POSEIDON_BLS12_381_A2_FC1,
+        }
+    }
+
+    fn from_codec(codec: u64) -> Result<Self> {
+        match codec {
+            FIL_COMMITMENT_UNSEALED => Ok(Self::Unsealed),
+            FIL_COMMITMENT_SEALED => Ok(Self::Sealed),
+            _ => Err(Error::UnknownCodec),
+        }
+    }
+}
+
+/// Checks the size and, for unsealed commitments, that the two most significant bits are unset.
+fn validate(kind: CommitmentKind, commitment: &[u8]) -> Result<Commitment> {
+    let commitment: Commitment = commitment.try_into().map_err(|_| Error::ParsingError)?;
+    if kind == CommitmentKind::Unsealed && commitment[COMMITMENT_LEN - 1] & 0b1100_0000 != 0 {
+        return Err(Error::ParsingError);
+    }
+    Ok(commitment)
+}
+
+/// Creates the CID of a commitment.
+///
+/// Fails if the commitment isn't 32 bytes, if an unsealed commitment isn't truncated to 254 bits
+/// or if `S` is smaller than 32.
+pub fn commitment_to_cid<const S: usize>(
+    kind: CommitmentKind,
+    commitment: &[u8],
+) -> Result<Cid<S>> {
+    let commitment = validate(kind, commitment)?;
+    let hash = Multihash::wrap(kind.hash_code(), &commitment)?;
+    Ok(Cid::new_v1(kind.codec(), hash))
+}
+
+/// Returns the kind and the commitment of a commitment CID.
+///
+/// The codec, multihash code and digest are validated.
+pub fn cid_to_commitment<const S: usize>(cid: &Cid<S>) -> Result<(CommitmentKind, Commitment)> {
+    if cid.version() != Version::V1 {
+        return Err(Error::InvalidCidVersion);
+    }
+    let kind = CommitmentKind::from_codec(cid.codec())?;
+    if cid.hash().code() != kind.hash_code() {
+        return Err(Error::ParsingError);
+    }
+    Ok((kind, validate(kind, cid.hash().digest())?))
+}
+
+/// Creates the CID of a piece commitment (CommP).
+pub fn piece_commitment_to_cid<const S: usize>(commitment: &[u8]) -> Result<Cid<S>> {
+    commitment_to_cid(CommitmentKind::Unsealed, commitment)
+}
+
+/// Creates the CID of a data commitment (CommD).
+pub fn data_commitment_to_cid<const S: usize>(commitment: &[u8]) -> Result<Cid<S>> {
+    commitment_to_cid(CommitmentKind::Unsealed, commitment)
+}
+
+/// Creates the CID of a replica commitment (CommR).
+pub fn replica_commitment_to_cid<const S: usize>(commitment: &[u8]) -> Result<Cid<S>> {
+    commitment_to_cid(CommitmentKind::Sealed, commitment)
+}
+
+/// Returns the commitment of an unsealed commitment CID.
+fn unsealed<const S: usize>(cid: &Cid<S>) -> Result<Commitment> {
+    match cid_to_commitment(cid)? {
+        (CommitmentKind::Unsealed, commitment) => Ok(commitment),
+        (CommitmentKind::Sealed, _) => Err(Error::UnknownCodec),
+    }
+}
+
+/// Returns the piece commitment (CommP) of a CID.
+pub fn cid_to_piece_commitment<const S: usize>(cid: &Cid<S>) -> Result<Commitment> {
+    unsealed(cid)
+}
+
+/// Returns the data commitment (CommD) of a CID.
+pub fn cid_to_data_commitment<const S: usize>(cid: &Cid<S>) -> Result<Commitment> {
+    unsealed(cid)
+}
+
+/// Returns the replica commitment (CommR) of a CID.
+pub fn cid_to_replica_commitment<const S: usize>(cid: &Cid<S>) -> Result<Commitment> {
+    match cid_to_commitment(cid)? {
+        (CommitmentKind::Sealed, commitment) => Ok(commitment),
+        (CommitmentKind::Unsealed, _) => Err(Error::UnknownCodec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let commitment = [0x3f; COMMITMENT_LEN];
+        let piece: Cid<64> = piece_commitment_to_cid(&commitment).unwrap();
+        assert!(piece.to_string().starts_with("baga6ea4sea"));
+        assert_eq!(cid_to_piece_commitment(&piece).unwrap(), commitment);
+        assert_eq!(cid_to_data_commitment(&piece).unwrap(), commitment);
+        assert!(cid_to_replica_commitment(&piece).is_err());
+
+        let commitment = [0xff; COMMITMENT_LEN];
+        let replica: Cid<64> = replica_commitment_to_cid(&commitment).unwrap();
+        assert_eq!(
+            cid_to_commitment(&replica).unwrap(),
+            (CommitmentKind::Sealed, commitment)
+        );
+        assert!(cid_to_piece_commitment(&replica).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(piece_commitment_to_cid::<64>(&[0; 31]).is_err());
+        assert!(piece_commitment_to_cid::<64>(&[0xff; 32]).is_err());
+        assert!(piece_commitment_to_cid::<16>(&[0; 32]).is_err());
+
+        let sha2 = Multihash::<64>::wrap(0x12, &[0; 32]).unwrap();
+        let wrong_hash = Cid::new_v1(FIL_COMMITMENT_UNSEALED, sha2);
+        assert!(matches!(
+            cid_to_commitment(&wrong_hash),
+            Err(Error::ParsingError)
+        ));
+        let wrong_codec = Cid::new_v1(0x55, sha2);
+        assert!(matches!(
+            cid_to_commitment(&wrong_codec),
+            Err(Error::UnknownCodec)
+        ));
+        let short = Multihash::<64>::wrap(SHA2_256_TRUNC254_PADDED, &[0; 16]).unwrap();
+        assert!(cid_to_commitment(&Cid::new_v1(FIL_COMMITMENT_UNSEALED, short)).is_err());
+    }
+}
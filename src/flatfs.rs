@@ -0,0 +1,193 @@
+//! The sharding functions of the flatfs datastore.
+//!
+//! flatfs, as used by go-ipfs/kubo, stores every block in a file named after its multihash, in
+//! a directory whose name is derived from that key. The function is recorded in the `SHARDING`
+//! file of the datastore, e.g. `/repo/flatfs/shard/v1/next-to-last/2`.
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use multibase::Base;
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+/// The prefix of the textual representation of a sharding function.
+const SPEC_PREFIX: &str = "/repo/flatfs/shard/v1/";
+
+/// Short keys are padded with this character.
+const PADDING: char = '_';
+
+/// A function that maps a key to the name of its shard directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShardFn {
+    /// The first `n` characters of the key.
+    Prefix(usize),
+    /// The last `n` characters of the key.
+    Suffix(usize),
+    /// The `n` characters before the last character of the key.
+    NextToLast(usize),
+}
+
+/// The default of go-ipfs/kubo, `next-to-last/2`.
+impl Default for ShardFn {
+    fn default() -> Self {
+        Self::NextToLast(2)
+    }
+}
+
+impl ShardFn {
+    /// Returns the shard directory name of a key.
+    ///
+    /// Fails with [`Error::ParsingError`] if the key isn't ASCII, as keys are base32.
+    pub fn shard(&self, key: &str) -> Result<String> {
+        if !key.is_ascii() {
+            return Err(Error::ParsingError);
+        }
+        // The key is ASCII, so that indexing by bytes is fine.
+        let shard = match *self {
+            Self::Prefix(n) => {
+                let mut shard = String::from(&key[..n.min(key.len())]);
+                shard.extend(core::iter::repeat(PADDING).take(n - shard.len()));
+                shard
+            }
+            Self::Suffix(n) => {
+                let padded = pad_front(key, n);
+                padded[padded.len() - n..].into()
+            }
+            Self::NextToLast(n) => {
+                let padded = pad_front(key, n + 1);
+                padded[padded.len() - n - 1..padded.len() - 1].into()
+            }
+        };
+        Ok(shard)
+    }
+
+    /// Returns the path of the file of a block, relative to the datastore root.
+    pub fn path<const S: usize>(&self, hash: &Multihash<S>) -> String {
+        let key = key(hash);
+        let shard = self.shard(&key).expect("base32 is ASCII");
+        format!("{}/{}.data", shard, key)
+    }
+
+    /// Returns the path of the file of the block of a CID, relative to the datastore root.
+    pub fn cid_path<const S: usize>(&self, cid: &Cid<S>) -> String {
+        self.path(cid.hash())
+    }
+}
+
+/// Returns the datastore key of a block, which is the unpadded, uppercase base32 multihash.
+///
+/// The codec isn't part of the key, all CIDs with the same multihash share the same block.
+pub fn key<const S: usize>(hash: &Multihash<S>) -> String {
+    Base::Base32Upper.encode(hash.to_bytes())
+}
+
+/// Pads the key with leading padding characters to at least `len` characters.
+fn pad_front(key: &str, len: usize) -> String {
+    let mut padded: String = core::iter::repeat(PADDING)
+        .take(len.saturating_sub(key.len()))
+        .collect();
+    padded.push_str(key);
+    padded
+}
+
+/// Formats the function the way it's stored in the `SHARDING` file.
+impl fmt::Display for ShardFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, n) = match self {
+            Self::Prefix(n) => ("prefix", n),
+            Self::Suffix(n) => ("suffix", n),
+            Self::NextToLast(n) => ("next-to-last", n),
+        };
+        write!(f, "{}{}/{}", SPEC_PREFIX, name, n)
+    }
+}
+
+/// Parses the contents of a `SHARDING` file.
+impl FromStr for ShardFn {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let (name, n) = spec
+            .trim_end()
+            .strip_prefix(SPEC_PREFIX)
+            .and_then(|spec| spec.split_once('/'))
+            .ok_or(Error::ParsingError)?;
+        let n = n.parse().map_err(|_| Error::ParsingError)?;
+        match name {
+            "prefix" => Ok(Self::Prefix(n)),
+            "suffix" => Ok(Self::Suffix(n)),
+            "next-to-last" => Ok(Self::NextToLast(n)),
+            _ => Err(Error::ParsingError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::{key, ShardFn};
+    use crate::Cid;
+
+    #[test]
+    fn shard() {
+        assert_eq!(ShardFn::Prefix(2).shard("ABCDE").unwrap(), "AB");
+        assert_eq!(ShardFn::Prefix(3).shard("A").unwrap(), "A__");
+        assert_eq!(ShardFn::Suffix(2).shard("ABCDE").unwrap(), "DE");
+        assert_eq!(ShardFn::Suffix(3).shard("A").unwrap(), "__A");
+        assert_eq!(ShardFn::NextToLast(2).shard("ABCDE").unwrap(), "CD");
+        assert_eq!(ShardFn::NextToLast(2).shard("AB").unwrap(), "_A");
+        assert_eq!(ShardFn::NextToLast(2).shard("").unwrap(), "__");
+
+        // Multi-byte characters are rejected instead of splitting them.
+        for shard_fn in [
+            ShardFn::Prefix(3),
+            ShardFn::Suffix(2),
+            ShardFn::NextToLast(2),
+        ] {
+            assert!(shard_fn.shard("AÄ").is_err());
+        }
+    }
+
+    #[test]
+    fn path() {
+        // The empty UnixFS directory, which is part of every go-ipfs repository.
+        let cid: Cid = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"
+            .parse()
+            .unwrap();
+        let expected = "CIQFTFEEHEDF6KLBT32BFAGLXEZL4UWFNWM4LFTLMXQBCERZ6CMLX3Y";
+        assert_eq!(key(cid.hash()), expected);
+        assert_eq!(
+            ShardFn::default().cid_path(&cid),
+            format!("X3/{}.data", expected)
+        );
+
+        let identity = Multihash::<64>::wrap(0x00, b"").unwrap();
+        assert_eq!(ShardFn::default().path(&identity), "AA/AAAA.data");
+    }
+
+    #[test]
+    fn spec() {
+        for shard_fn in [
+            ShardFn::Prefix(1),
+            ShardFn::Suffix(3),
+            ShardFn::NextToLast(2),
+        ] {
+            assert_eq!(shard_fn.to_string().parse::<ShardFn>().unwrap(), shard_fn);
+        }
+        assert_eq!(
+            "/repo/flatfs/shard/v1/next-to-last/2\n"
+                .parse::<ShardFn>()
+                .unwrap(),
+            ShardFn::default()
+        );
+        assert!("/repo/flatfs/shard/v1/last/2".parse::<ShardFn>().is_err());
+        assert!("/repo/flatfs/shard/v2/prefix/2".parse::<ShardFn>().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! HTTP gateway URLs.
+//!
+//! Content can be retrieved from a gateway either path style, `https://<gateway>/ipfs/<cid>/<path>`,
+//! or subdomain style, `https://<cid>.ipfs.<gateway>/<path>`. The latter gives every CID its own
+//! origin, but requires a case-insensitive CID of at most 63 characters, hence CIDv0 are
+//! converted to CIDv1 and base36 is used if base32 is too long.
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+/// The maximum length of a DNS label.
+const MAX_LABEL_LEN: usize = 63;
+
+/// An HTTP gateway.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gateway {
+    scheme: String,
+    host: String,
+}
+
+impl Gateway {
+    /// Creates a gateway from its origin, e.g. `https://dweb.link`.
+    ///
+    /// A trailing slash is allowed, any other path is not.
+    pub fn new(origin: &str) -> Result<Self> {
+        let (scheme, host) = origin.split_once("://").ok_or(Error::ParsingError)?;
+        let host = host.strip_suffix('/').unwrap_or(host);
+        if scheme.is_empty() || host.is_empty() || host.contains(['/', '?', '#']) {
+            return Err(Error::ParsingError);
+        }
+        Ok(Self {
+            scheme: scheme.to_ascii_lowercase(),
+            host: host.to_ascii_lowercase(),
+        })
+    }
+
+    /// Returns the path style URL of a CID and a path within it.
+    ///
+    /// The path is percent-encoded, its segments are separated by `/`.
+    pub fn path_url<const S: usize>(&self, cid: &Cid<S>, path: &str) -> String {
+        let mut url = format!("{}://{}/ipfs/{}", self.scheme, self.host, cid);
+        push_path(&mut url, path);
+        url
+    }
+
+    /// Returns the subdomain style URL of a CID and a path within it.
+    ///
+    /// The path is percent-encoded, its segments are separated by `/`. Fails if the CID doesn't
+    /// fit into a DNS label, even in base36.
+    pub fn subdomain_url<const S: usize>(&self, cid: &Cid<S>, path: &str) -> Result<String> {
+        let label = cid.to_dns_label()?;
+        let mut url = format!("{}://{}.ipfs.{}", self.scheme, label, self.host);
+        push_path(&mut url, path);
+        Ok(url)
+    }
+}
+
+/// The style of a gateway URL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GatewayStyle {
+    /// `https://<gateway>/ipfs/<cid>/`
+    Path,
+    /// `https://<cid>.ipfs.<gateway>/`
+    Subdomain,
+}
+
+impl<const S: usize> Cid<S> {
+    /// Returns the URL of the CID on a gateway, given by its origin, e.g. `https://dweb.link`.
+    ///
+    /// See [`Gateway::path_url`] and [`Gateway::subdomain_url`].
+    pub fn to_gateway_url(&self, origin: &str, style: GatewayStyle) -> Result<String> {
+        let gateway = Gateway::new(origin)?;
+        match style {
+            GatewayStyle::Path => Ok(gateway.path_url(self, "")),
+            GatewayStyle::Subdomain => gateway.subdomain_url(self, ""),
+        }
+    }
+
+    /// Returns the CID as a DNS label, e.g. for subdomain gateways.
+    ///
+    /// It's the CIDv1 in base32, or in base36 if that is longer than the 63 characters a label may
+    /// have. Fails with [`Error::DnsLabelTooLong`] if neither fits.
+    pub fn to_dns_label(&self) -> Result<String> {
+        let cid = self.into_v1()?;
+        let label = cid.to_string();
+        if label.len() <= MAX_LABEL_LEN {
+            return Ok(label);
+        }
+        let label = cid.to_string_base36();
+        if label.len() <= MAX_LABEL_LEN {
+            Ok(label)
+        } else {
+            Err(Error::DnsLabelTooLong)
+        }
+    }
+
+    /// Parses the CID of a subdomain style gateway hostname, e.g. `<cid>.ipfs.dweb.link`.
+    ///
+    /// The CID is the first label and must be followed by an `ipfs` label, both are matched
+    /// case-insensitively. A port is allowed.
+    pub fn from_gateway_host(host: &str) -> Result<Self> {
+        let (label, rest) = host.split_once('.').ok_or(Error::ParsingError)?;
+        let namespace = rest.split(['.', ':']).next().unwrap_or_default();
+        if !namespace.eq_ignore_ascii_case("ipfs") || namespace.len() == rest.len() {
+            return Err(Error::ParsingError);
+        }
+        Self::parse_strict(label)
+    }
+}
+
+/// Appends the percent-encoded path, always starting with a `/`.
+fn push_path(url: &mut String, path: &str) {
+    url.push('/');
+    let path = path.strip_prefix('/').unwrap_or(path);
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => url.push(byte.into()),
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+'
+            | b',' | b';' | b'=' | b':' | b'@' | b'/' => url.push(byte.into()),
+            _ => write!(url, "%{:02X}", byte).expect("writing to a string never fails"),
+        }
+    }
+}
+
+impl fmt::Display for Gateway {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)
+    }
+}
+
+impl FromStr for Gateway {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multibase::Base;
+    use multihash::Multihash;
+
+    use super::{Gateway, GatewayStyle};
+    use crate::{Cid, Error};
+
+    #[test]
+    fn urls() {
+        let gateway = Gateway::new("https://dweb.link/").unwrap();
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        assert_eq!(
+            gateway.path_url(&cid, "/a b/ü.html"),
+            "https://dweb.link/ipfs/QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n/a%20b/%C3%BC.html"
+        );
+        assert_eq!(
+            gateway.subdomain_url(&cid, "").unwrap(),
+            "https://bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipfs.dweb.link/"
+        );
+
+        // The 40 bytes result in 65 characters in base32, but only 63 in base36.
+        let long = Cid::new_v1(0x55, Multihash::wrap(0x00, &[0xff; 36]).unwrap());
+        let url = gateway.subdomain_url(&long, "x?").unwrap();
+        assert!(url.starts_with("https://k"));
+        assert!(url.ends_with(".ipfs.dweb.link/x%3F"));
+
+        let too_long = Cid::new_v1(0x55, Multihash::wrap(0x12, &[0xff; 64]).unwrap());
+        assert!(gateway.subdomain_url(&too_long, "").is_err());
+    }
+
+    #[test]
+    fn dns_label() {
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        assert_eq!(
+            cid.to_dns_label().unwrap(),
+            "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku"
+        );
+        let long = Cid::new_v1(0x55, Multihash::wrap(0x00, &[0xff; 36]).unwrap());
+        assert_eq!(long.to_dns_label().unwrap(), long.to_string_base36());
+        assert!(long.to_string().len() > 63);
+        let too_long = Cid::new_v1(0x55, Multihash::wrap(0x12, &[0xff; 64]).unwrap());
+        assert!(matches!(
+            too_long.to_dns_label(),
+            Err(Error::DnsLabelTooLong)
+        ));
+    }
+
+    #[test]
+    fn cid_to_gateway_url() {
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        assert_eq!(
+            cid.to_gateway_url("https://ipfs.io", GatewayStyle::Path)
+                .unwrap(),
+            "https://ipfs.io/ipfs/QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n/"
+        );
+        let url = cid
+            .to_gateway_url("http://localhost:8080/", GatewayStyle::Subdomain)
+            .unwrap();
+        assert_eq!(
+            url,
+            "http://bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipfs.localhost:8080/"
+        );
+        assert!(cid.to_gateway_url("ipfs.io", GatewayStyle::Path).is_err());
+    }
+
+    #[test]
+    fn gateway_host() {
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n")
+            .unwrap()
+            .into_v1()
+            .unwrap();
+        for host in [
+            "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipfs.dweb.link",
+            "BAFYBEIHDWDCEFGH4DQKJV67UZCMW7OJEE6XEDZDETOJUZJEVTENXQUVYKU.IPFS.DWEB.LINK",
+            "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipfs.localhost:8080",
+        ] {
+            assert_eq!(Cid::from_gateway_host(host).unwrap(), cid, "{}", host);
+        }
+        let base36 = cid.to_string_of_base(Base::Base36Lower).unwrap();
+        let host = format!("{}.ipfs.dweb.link", base36.to_uppercase());
+        assert_eq!(Cid::from_gateway_host(&host).unwrap(), cid);
+
+        for host in [
+            "dweb.link",
+            "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipns.dweb.link",
+            "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku.ipfs",
+            "www.ipfs.dweb.link",
+        ] {
+            assert!(Cid::from_gateway_host(host).is_err(), "{}", host);
+        }
+    }
+
+    #[test]
+    fn origin() {
+        assert_eq!(
+            Gateway::new("HTTP://localhost:8080").unwrap().to_string(),
+            "http://localhost:8080"
+        );
+        assert!(Gateway::new("dweb.link").is_err());
+        assert!(Gateway::new("https://dweb.link/ipfs").is_err());
+    }
+}
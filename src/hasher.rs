@@ -0,0 +1,92 @@
+//! Hashing of CIDs for use as keys in hash maps and sets.
+//!
+//! A CID already carries a cryptographic digest, hence running it through another hash function
+//! like SipHash is wasted work. The [`CidHasher`] takes the leading bytes of the digest as the
+//! hash value instead.
+//!
+//! This is only safe for CIDs whose multihash is a cryptographic hash. CIDs using the identity
+//! hash (or any other non-cryptographic hash) coming from untrusted sources could be used to
+//! provoke hash collisions.
+use core::hash::{BuildHasherDefault, Hasher};
+
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// A [`Hasher`] that passes through the leading bytes of a CID digest.
+///
+/// Integers that are hashed (the version, codec and multihash code) are only mixed in cheaply.
+/// The digest, which is the only slice that is hashed by a CID, provides the entropy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CidHasher(u64);
+
+impl Hasher for CidHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut buf = [0u8; 8];
+        let len = bytes.len().min(buf.len());
+        buf[..len].copy_from_slice(&bytes[..len]);
+        self.0 ^= u64::from_le_bytes(buf);
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i.into())
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i.into())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i.into())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0 ^= i;
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as u64)
+    }
+}
+
+/// A [`core::hash::BuildHasher`] creating [`CidHasher`]s.
+pub type BuildCidHasher = BuildHasherDefault<CidHasher>;
+
+/// A [`HashMap`] keyed by CIDs that uses the [`CidHasher`].
+#[cfg(feature = "std")]
+pub type FastCidMap<V> = HashMap<crate::Cid, V, BuildCidHasher>;
+
+/// A [`HashSet`] of CIDs that uses the [`CidHasher`].
+#[cfg(feature = "std")]
+pub type FastCidSet = HashSet<crate::Cid, BuildCidHasher>;
+
+#[cfg(test)]
+mod tests {
+    use core::hash::{BuildHasher, Hash, Hasher};
+
+    use multihash::Multihash;
+
+    use super::{BuildCidHasher, CidHasher};
+    use crate::Cid;
+
+    #[test]
+    fn uses_digest_bytes() {
+        let digest = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let cid = Cid::new_v1(0x55, Multihash::wrap(0x12, &digest).unwrap());
+
+        let mut hasher = BuildCidHasher::default().build_hasher();
+        Hash::hash(&cid, &mut hasher);
+
+        let mut digest_only = CidHasher::default();
+        digest_only.write(&digest);
+        // The integers that were mixed in only affect the lower bits.
+        assert_eq!(hasher.finish() >> 16, digest_only.finish() >> 16);
+    }
+}
@@ -0,0 +1,107 @@
+//! Constructors hashing the data with popular hash functions beyond SHA2-256.
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::error::Result;
+
+/// The BLAKE3 multihash code.
+#[cfg(feature = "blake3")]
+const BLAKE3: u64 = 0x1e;
+/// The SHA3-512 multihash code.
+#[cfg(feature = "sha3")]
+const SHA3_512: u64 = 0x14;
+/// The SHA3-256 multihash code.
+#[cfg(feature = "sha3")]
+const SHA3_256: u64 = 0x16;
+/// The Keccak-256 multihash code.
+#[cfg(feature = "sha3")]
+const KECCAK_256: u64 = 0x1b;
+
+#[cfg(feature = "sha3")]
+fn digest<D: sha3::Digest, const S: usize>(code: u64, data: &[u8]) -> Result<Multihash<S>> {
+    Ok(Multihash::wrap(code, &D::digest(data))?)
+}
+
+impl<const S: usize> Cid<S> {
+    /// Creates a CIDv1 of the data hashed with BLAKE3, with a 32 byte digest.
+    ///
+    /// Fails if `S` is smaller than the digest.
+    #[cfg(feature = "blake3")]
+    pub fn new_v1_blake3(codec: u64, data: &[u8]) -> Result<Self> {
+        let hash = Multihash::wrap(BLAKE3, blake3::hash(data).as_bytes())?;
+        Ok(Self::new_v1(codec, hash))
+    }
+
+    /// Creates a CIDv1 of the data hashed with SHA3-256.
+    ///
+    /// Fails if `S` is smaller than the digest.
+    #[cfg(feature = "sha3")]
+    pub fn new_v1_sha3_256(codec: u64, data: &[u8]) -> Result<Self> {
+        Ok(Self::new_v1(
+            codec,
+            digest::<sha3::Sha3_256, S>(SHA3_256, data)?,
+        ))
+    }
+
+    /// Creates a CIDv1 of the data hashed with SHA3-512.
+    ///
+    /// Fails if `S` is smaller than the digest.
+    #[cfg(feature = "sha3")]
+    pub fn new_v1_sha3_512(codec: u64, data: &[u8]) -> Result<Self> {
+        Ok(Self::new_v1(
+            codec,
+            digest::<sha3::Sha3_512, S>(SHA3_512, data)?,
+        ))
+    }
+
+    /// Creates a CIDv1 of the data hashed with Keccak-256, as used by Ethereum.
+    ///
+    /// Fails if `S` is smaller than the digest.
+    #[cfg(feature = "sha3")]
+    pub fn new_v1_keccak_256(codec: u64, data: &[u8]) -> Result<Self> {
+        Ok(Self::new_v1(
+            codec,
+            digest::<sha3::Keccak256, S>(KECCAK_256, data)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cid, CidGeneric};
+
+    const RAW: u64 = 0x55;
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3() {
+        let cid = Cid::new_v1_blake3(RAW, b"").unwrap();
+        assert_eq!(cid.hash().code(), 0x1e);
+        assert_eq!(
+            multibase::Base::Base16Lower.encode(cid.hash().digest()),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert!(CidGeneric::<16>::new_v1_blake3(RAW, b"").is_err());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn sha3() {
+        let cid = Cid::new_v1_sha3_256(RAW, b"").unwrap();
+        assert_eq!(cid.hash().code(), 0x16);
+        assert_eq!(
+            multibase::Base::Base16Lower.encode(cid.hash().digest()),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        let cid = Cid::new_v1_keccak_256(RAW, b"").unwrap();
+        assert_eq!(cid.hash().code(), 0x1b);
+        assert_eq!(
+            multibase::Base::Base16Lower.encode(cid.hash().digest()),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        let cid = Cid::new_v1_sha3_512(RAW, b"").unwrap();
+        assert_eq!(cid.hash().code(), 0x14);
+        assert_eq!(cid.hash().size(), 64);
+        assert!(CidGeneric::<32>::new_v1_sha3_512(RAW, b"").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! IPNS names.
+extern crate alloc;
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use multibase::Base;
+use multihash::Multihash;
+
+use crate::cid::{Cid, LIBP2P_KEY, SHA2_256};
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// The identity multihash code.
+const IDENTITY: u64 = 0x00;
+
+/// The maximum size of a public key that is inlined into a peer ID instead of being hashed.
+const MAX_INLINE_KEY_LEN: usize = 42;
+
+/// An IPNS name, the peer ID of the key the records are signed with.
+///
+/// It's parsed from either a CIDv1 with the `libp2p-key` codec in any base, or from the legacy
+/// representation of a peer ID, the plain base58btc encoded multihash (`Qm…` or `12D3Koo…`).
+/// Its canonical representation, used for display, is the CIDv1 in base36, e.g. `k51…` for
+/// Ed25519 keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpnsName<const S: usize = 64> {
+    cid: Cid<S>,
+}
+
+impl<const S: usize> IpnsName<S> {
+    /// Creates an IPNS name from a CIDv1 with the `libp2p-key` codec.
+    ///
+    /// The multihash must either be an identity hash of at most 42 bytes or a SHA2-256 hash.
+    pub fn new(cid: Cid<S>) -> Result<Self> {
+        if cid.version() != Version::V1 {
+            return Err(Error::InvalidCidVersion);
+        }
+        if cid.codec() != LIBP2P_KEY {
+            return Err(Error::UnknownCodec);
+        }
+        Self::from_hash(*cid.hash())
+    }
+
+    /// Creates an IPNS name from the multihash of a peer ID.
+    pub fn from_hash(hash: Multihash<S>) -> Result<Self> {
+        match (hash.code(), hash.size() as usize) {
+            (IDENTITY, size) if size <= MAX_INLINE_KEY_LEN => {}
+            (SHA2_256, 32) => {}
+            _ => return Err(Error::ParsingError),
+        }
+        Ok(Self {
+            cid: Cid::new_v1(LIBP2P_KEY, hash),
+        })
+    }
+
+    /// Returns the CID of the name.
+    pub const fn cid(&self) -> &Cid<S> {
+        &self.cid
+    }
+
+    /// Returns the multihash of the peer ID.
+    pub const fn hash(&self) -> &Multihash<S> {
+        self.cid.hash()
+    }
+
+    /// Returns the legacy representation, the base58btc encoded multihash.
+    pub fn to_legacy_string(&self) -> String {
+        Base::Base58Btc.encode(self.hash().to_bytes())
+    }
+}
+
+impl<const S: usize> TryFrom<Cid<S>> for IpnsName<S> {
+    type Error = Error;
+
+    fn try_from(cid: Cid<S>) -> Result<Self> {
+        Self::new(cid)
+    }
+}
+
+impl<const S: usize> From<IpnsName<S>> for Cid<S> {
+    fn from(name: IpnsName<S>) -> Self {
+        name.cid
+    }
+}
+
+impl<const S: usize> FromStr for IpnsName<S> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("Qm") || s.starts_with('1') {
+            let bytes = Base::Base58Btc.decode(s)?;
+            Self::from_hash(Multihash::from_bytes(&bytes)?)
+        } else {
+            Self::new(Cid::parse_strict(s)?)
+        }
+    }
+}
+
+impl<const S: usize> TryFrom<&str> for IpnsName<S> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl<const S: usize> fmt::Display for IpnsName<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&multibase::encode(Base::Base36Lower, self.cid.to_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use crate::{Cid, Error, IpnsName, LIBP2P_KEY};
+
+    #[test]
+    fn normalize() {
+        let canonical = "k51qzi5uqu5dhdmyb9bd18pypu2wp5lpv2xnskfmrqa4lb5knqryrotb05e7or";
+        let legacy = "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA";
+        let name: IpnsName = legacy.parse().unwrap();
+        assert_eq!(name.to_string(), canonical);
+        assert_eq!(name.to_legacy_string(), legacy);
+        assert_eq!(canonical.parse::<IpnsName>().unwrap(), name);
+
+        let base32 = name.cid().to_string();
+        assert!(base32.starts_with("bafz"));
+        assert_eq!(base32.parse::<IpnsName>().unwrap(), name);
+
+        let rsa: IpnsName = "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N"
+            .parse()
+            .unwrap();
+        assert!(rsa.to_string().starts_with("k2k4r8"));
+        assert_eq!(
+            rsa.to_legacy_string(),
+            "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let sha2 = Multihash::wrap(0x12, &[0; 32]).unwrap();
+        assert!(matches!(
+            IpnsName::new(Cid::new_v0(sha2).unwrap()),
+            Err(Error::InvalidCidVersion)
+        ));
+        assert!(matches!(
+            IpnsName::new(Cid::new_v1(0x55, sha2)),
+            Err(Error::UnknownCodec)
+        ));
+        let short = Multihash::wrap(0x12, &[0; 20]).unwrap();
+        assert!(IpnsName::new(Cid::new_v1(LIBP2P_KEY, short)).is_err());
+        let long = Multihash::wrap(0x00, &[0; 43]).unwrap();
+        assert!(IpnsName::new(Cid::new_v1(LIBP2P_KEY, long)).is_err());
+        assert!(
+            "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+                .parse::<IpnsName>()
+                .is_err()
+        );
+
+        // Only the name itself, not a path or URL containing it.
+        let canonical = "k51qzi5uqu5dhdmyb9bd18pypu2wp5lpv2xnskfmrqa4lb5knqryrotb05e7or";
+        assert!(canonical.parse::<IpnsName>().is_ok());
+        for path in [
+            format!("/ipfs/{}", canonical),
+            format!("https://dweb.link/ipfs/{}", canonical),
+        ] {
+            assert!(path.parse::<IpnsName>().is_err(), "{}", path);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Iterator adapters for CIDs.
+use crate::cid::Cid;
+use crate::collections::CidSet;
+
+/// Returns an iterator that yields every CID only once, in the order they first appear.
+///
+/// The CIDs seen so far are kept in a [`CidSet`], so the deduplication is exact.
+pub fn dedup<I, const S: usize>(cids: I) -> Dedup<I::IntoIter, S>
+where
+    I: IntoIterator<Item = Cid<S>>,
+{
+    Dedup {
+        iter: cids.into_iter(),
+        seen: CidSet::new(),
+    }
+}
+
+/// An iterator that skips CIDs that were already yielded, see [`dedup`].
+#[derive(Clone, Debug)]
+pub struct Dedup<I, const S: usize = 64> {
+    iter: I,
+    seen: CidSet<S>,
+}
+
+impl<I, const S: usize> Dedup<I, S> {
+    /// Returns the CIDs that were yielded so far.
+    pub fn seen(&self) -> &CidSet<S> {
+        &self.seen
+    }
+}
+
+impl<I: Iterator<Item = Cid<S>>, const S: usize> Iterator for Dedup<I, S> {
+    type Item = Cid<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.iter.find(|cid| seen.insert(*cid))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        // Once a CID was yielded, all remaining ones may be duplicates.
+        if self.seen.is_empty() {
+            (lower.min(1), upper)
+        } else {
+            (0, upper)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::dedup;
+    use crate::Cid;
+
+    #[test]
+    fn dedup_keeps_first() {
+        let cid = |n: u8| Cid::new_v1(0x55, Multihash::wrap(0x12, &[n; 32]).unwrap());
+        let cids = [cid(1), cid(2), cid(1), cid(3), cid(2), cid(2)];
+        let deduped: Vec<_> = dedup(cids).collect();
+        assert_eq!(deduped, [cid(1), cid(2), cid(3)]);
+
+        let mut iter = dedup(cids);
+        iter.next();
+        assert_eq!(iter.seen().len(), 1);
+    }
+
+    #[test]
+    fn size_hint() {
+        let cid = Cid::new_v1(0x55, Multihash::wrap(0x12, &[1; 32]).unwrap());
+        let mut iter = dedup([cid, cid]);
+        assert_eq!(iter.size_hint(), (1, Some(2)));
+        assert_eq!(iter.next(), Some(cid));
+        assert_eq!(iter.size_hint(), (0, Some(1)));
+        assert_eq!(iter.next(), None);
+    }
+}
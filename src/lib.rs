@@ -5,23 +5,158 @@
 #![deny(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+mod base32;
+#[cfg(feature = "alloc")]
+mod base58;
+#[cfg(feature = "std")]
+mod bloom;
 mod cid;
+#[cfg(feature = "alloc")]
+mod cid_vec;
+#[cfg(feature = "std")]
+mod collections;
 mod error;
+#[cfg(any(feature = "blake3", feature = "sha3"))]
+mod hashes;
+#[cfg(feature = "alloc")]
+mod ipns;
+mod link;
+#[cfg(feature = "alloc")]
+mod parser;
+#[cfg(feature = "alloc")]
+mod path;
+#[cfg(feature = "alloc")]
+mod sorted;
+mod trace;
+#[cfg(feature = "alloc")]
+mod trie;
+mod unchecked;
+#[cfg(feature = "alloc")]
+mod uri;
+#[cfg(kani)]
+mod verification;
 mod version;
 
+pub mod hasher;
+pub mod varint;
+
+#[cfg(feature = "actix-web")]
+mod actix;
 #[cfg(any(test, feature = "arb"))]
 mod arb;
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "bitcoin")]
+pub mod bitcoin;
+#[cfg(feature = "car")]
+pub mod car;
+#[cfg(feature = "alloc")]
+pub mod compat;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "ens")]
+pub mod ens;
+#[cfg(feature = "ethereum")]
+pub mod ethereum;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "filecoin")]
+pub mod filecoin;
+#[cfg(feature = "alloc")]
+pub mod flatfs;
+#[cfg(feature = "alloc")]
+pub mod gateway;
+#[cfg(feature = "std")]
+pub mod iter;
+#[cfg(feature = "libp2p")]
+mod libp2p;
+#[cfg(feature = "alloc")]
+pub mod merkle;
+#[cfg(feature = "multiaddr")]
+mod multiaddr;
+#[cfg(feature = "pyo3")]
+pub mod python;
+#[cfg(feature = "rand")]
+pub mod random;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "sea-orm")]
+mod sea_orm;
 #[cfg(feature = "serde")]
 pub mod serde;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "uniffi")]
+pub mod uniffi_bindings;
 
+#[cfg(feature = "std")]
+pub use self::bloom::CidBloom;
 pub use self::cid::Cid as CidGeneric;
+pub use self::cid::CidBuilder;
+pub use self::cid::LIBP2P_KEY;
+#[cfg(feature = "alloc")]
+pub use self::cid_vec::{CidRef, CidVec, CidVecIter};
+#[cfg(feature = "std")]
+pub use self::collections::{CidMap, CidSet};
 pub use self::error::{Error, Result};
+#[cfg(feature = "alloc")]
+pub use self::ipns::IpnsName;
+pub use self::link::Link;
+#[cfg(feature = "alloc")]
+pub use self::parser::CidParser;
+#[cfg(feature = "alloc")]
+pub use self::path::IpfsPath;
+#[cfg(feature = "alloc")]
+pub use self::sorted::{SortedCidIter, SortedCidList};
+#[cfg(feature = "alloc")]
+pub use self::trie::{CidTrie, CidTrieIter};
+pub use self::unchecked::UncheckedCid;
+#[cfg(feature = "alloc")]
+pub use self::uri::{ContentUri, UriRoot};
 pub use self::version::Version;
 
+#[cfg(feature = "derive")]
+pub use cid_derive::CidWrapper;
+
 #[cfg(feature = "alloc")]
 pub use multibase;
 pub use multihash;
 
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+/// Items used by the code generated by `CidWrapper`, not public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "serde")]
+    pub use ::serde;
+}
+
+/// Expands the items if the `serde` feature is enabled.
+#[doc(hidden)]
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! __if_serde {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Expands the items if the `serde` feature is enabled.
+#[doc(hidden)]
+#[cfg(not(feature = "serde"))]
+#[macro_export]
+macro_rules! __if_serde {
+    ($($item:item)*) => {};
+}
+
 // Doctest the readme!
 #[doc = include_str!("../README.md")]
 #[cfg(all(doctest, feature = "std"))]
@@ -0,0 +1,82 @@
+//! Conversions between CIDs and libp2p peer IDs.
+//!
+//! A peer ID is a multihash of a public key. As a CID, it's a CIDv1 with the `libp2p-key` codec
+//! wrapping that multihash, which is e.g. used for IPNS names.
+use libp2p_identity::PeerId;
+use multihash::Multihash;
+
+use crate::cid::{Cid, LIBP2P_KEY};
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+impl<const S: usize> Cid<S> {
+    /// Creates a CIDv1 with the `libp2p-key` codec from a peer ID.
+    ///
+    /// Fails if the multihash of the peer ID doesn't fit into `S` bytes.
+    pub fn from_peer_id(peer_id: &PeerId) -> Result<Self> {
+        let hash = peer_id.as_ref();
+        let hash = Multihash::wrap(hash.code(), hash.digest())?;
+        Ok(Self::new_v1(LIBP2P_KEY, hash))
+    }
+
+    /// Returns the peer ID of a CIDv1 with the `libp2p-key` codec.
+    ///
+    /// The multihash must either be an identity hash of at most 42 bytes or a SHA2-256 hash, as
+    /// required for peer IDs.
+    pub fn to_peer_id(&self) -> Result<PeerId> {
+        if self.version() != Version::V1 {
+            return Err(Error::InvalidCidVersion);
+        }
+        if self.codec() != LIBP2P_KEY {
+            return Err(Error::UnknownCodec);
+        }
+        let hash = Multihash::wrap(self.hash().code(), self.hash().digest())?;
+        PeerId::from_multihash(hash).map_err(|_| Error::ParsingError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use libp2p_identity::PeerId;
+    use multihash::Multihash;
+
+    use crate::{Cid, CidGeneric, Error};
+
+    #[test]
+    fn peer_id_roundtrip() {
+        for s in [
+            "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA",
+            "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N",
+        ] {
+            let peer_id = PeerId::from_str(s).unwrap();
+            let cid = Cid::from_peer_id(&peer_id).unwrap();
+            assert_eq!(cid.codec(), crate::LIBP2P_KEY);
+            assert!(cid.to_string().starts_with("bafz"));
+            assert_eq!(cid.to_peer_id().unwrap(), peer_id);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let peer_id = PeerId::from_str("QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N").unwrap();
+        assert!(CidGeneric::<16>::from_peer_id(&peer_id).is_err());
+
+        let sha2 = *Cid::from_peer_id(&peer_id).unwrap().hash();
+        assert!(matches!(
+            Cid::new_v0(sha2).unwrap().to_peer_id(),
+            Err(Error::InvalidCidVersion)
+        ));
+        assert!(matches!(
+            Cid::new_v1(0x55, sha2).to_peer_id(),
+            Err(Error::UnknownCodec)
+        ));
+
+        let blake3 = Multihash::wrap(0x1e, &[0; 32]).unwrap();
+        assert!(matches!(
+            Cid::new_v1(crate::LIBP2P_KEY, blake3).to_peer_id(),
+            Err(Error::ParsingError)
+        ));
+    }
+}
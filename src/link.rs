@@ -0,0 +1,201 @@
+//! CIDs typed with what they link to.
+//!
+//! A [`Link<T>`] is a CID that is expected to decode to a `T`. It has the same representation as a
+//! CID, the type only documents DAG schemas and prevents mixing up links to different node types:
+//!
+//! ```
+//! use cid::{Cid, Link};
+//!
+//! struct Commit {
+//!     parents: Vec<Link<Commit>>,
+//!     tree: Link<Tree>,
+//! }
+//! struct Tree;
+//!
+//! let cid: Cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy".parse().unwrap();
+//! let tree: Link<Tree> = Link::new(cid);
+//! let commit = Commit { parents: Vec::new(), tree };
+//! assert_eq!(commit.tree.cid(), &cid);
+//! ```
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+
+use crate::cid::Cid;
+#[cfg(feature = "alloc")]
+use crate::error::{Error, Result};
+
+/// A CID that is expected to decode to a `T`.
+///
+/// The traits are implemented independently of `T`, which is never stored.
+pub struct Link<T, const S: usize = 64> {
+    cid: Cid<S>,
+    // `fn() -> T` keeps the link `Send`, `Sync` and covariant whatever `T` is.
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T, const S: usize> Link<T, S> {
+    /// Creates a link from a CID, which isn't checked to decode to a `T`.
+    pub const fn new(cid: Cid<S>) -> Self {
+        Self {
+            cid,
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns the CID.
+    pub const fn cid(&self) -> &Cid<S> {
+        &self.cid
+    }
+
+    /// Returns the CID, dropping the type.
+    pub const fn into_cid(self) -> Cid<S> {
+        self.cid
+    }
+
+    /// Reinterprets the link as one to a `U`.
+    pub const fn cast<U>(self) -> Link<U, S> {
+        Link::new(self.cid)
+    }
+}
+
+impl<T, const S: usize> Clone for Link<T, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const S: usize> Copy for Link<T, S> {}
+
+impl<T, const S: usize> PartialEq for Link<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cid == other.cid
+    }
+}
+
+impl<T, const S: usize> Eq for Link<T, S> {}
+
+impl<T, const S: usize> PartialOrd for Link<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, const S: usize> Ord for Link<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cid.cmp(&other.cid)
+    }
+}
+
+impl<T, const S: usize> Hash for Link<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.cid, state)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const S: usize> fmt::Debug for Link<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Link").field(&self.cid).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const S: usize> fmt::Display for Link<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.cid, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const S: usize> FromStr for Link<T, S> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Self::new(s.parse()?))
+    }
+}
+
+impl<T, const S: usize> Deref for Link<T, S> {
+    type Target = Cid<S>;
+
+    fn deref(&self) -> &Cid<S> {
+        &self.cid
+    }
+}
+
+impl<T, const S: usize> AsRef<Cid<S>> for Link<T, S> {
+    fn as_ref(&self) -> &Cid<S> {
+        &self.cid
+    }
+}
+
+impl<T, const S: usize> From<Cid<S>> for Link<T, S> {
+    fn from(cid: Cid<S>) -> Self {
+        Self::new(cid)
+    }
+}
+
+impl<T, const S: usize> From<Link<T, S>> for Cid<S> {
+    fn from(link: Link<T, S>) -> Self {
+        link.cid
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const S: usize> serde::Serialize for Link<T, S> {
+    fn serialize<Ser: serde::Serializer>(
+        &self,
+        serializer: Ser,
+    ) -> core::result::Result<Ser::Ok, Ser::Error> {
+        serde::Serialize::serialize(&self.cid, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const S: usize> serde::Deserialize<'de> for Link<T, S> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        serde::Deserialize::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Block;
+    struct Manifest;
+
+    const CID: &str = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+
+    #[test]
+    fn link() {
+        let cid: Cid<64> = CID.parse().unwrap();
+        let block: Link<Block> = CID.parse().unwrap();
+        assert_eq!(block.cid(), &cid);
+        assert_eq!(block.codec(), 0x55);
+        assert_eq!(block.to_string(), CID);
+        assert_eq!(block, Link::from(cid));
+        assert_eq!(block.cast::<Manifest>().into_cid(), cid);
+        assert_eq!(
+            core::mem::size_of::<Link<Block>>(),
+            core::mem::size_of::<Cid<64>>()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let block: Link<Block> = CID.parse().unwrap();
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(json, serde_json::to_string(block.cid()).unwrap());
+        assert_eq!(serde_json::from_str::<Link<Block>>(&json).unwrap(), block);
+    }
+}
@@ -0,0 +1,134 @@
+//! Merkle roots over ordered lists of CIDs.
+//!
+//! The tree is binary and built bottom up, with domain separation as in [RFC 6962]. All nodes are
+//! CIDv1 with the given codec. A leaf is the hash of a `0x00` byte followed by the binary CID of a
+//! list entry. On every level, neighbouring nodes are paired, the parent of a pair is the hash of
+//! a `0x01` byte followed by the binary representations of both nodes. If a level has an odd
+//! number of nodes, the last one is moved up unchanged. This is repeated until a single node, the
+//! root, is left. The root of an empty list is the hash of the empty input.
+//!
+//! The prefixes ensure that a leaf can't be mistaken for an inner node, hence the root commits to
+//! the length of the list, not just to the hashes on some level of the tree.
+//!
+//! [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use multihash::Multihash;
+
+use crate::cid::Cid;
+
+/// The prefix of the hashed input of leaves.
+const LEAF_PREFIX: u8 = 0x00;
+/// The prefix of the hashed input of inner nodes.
+const NODE_PREFIX: u8 = 0x01;
+
+/// Returns the Merkle root of an ordered list of CIDs.
+///
+/// `hasher` computes the multihash of the nodes, e.g. `|bytes| Code::Sha2_256.digest(bytes)`.
+pub fn root<const S: usize, I, F>(cids: I, codec: u64, mut hasher: F) -> Cid<S>
+where
+    I: IntoIterator<Item = Cid<S>>,
+    F: FnMut(&[u8]) -> Multihash<S>,
+{
+    let mut node = Vec::new();
+    let mut level: Vec<Cid<S>> = cids
+        .into_iter()
+        .map(|cid| {
+            node.clear();
+            node.push(LEAF_PREFIX);
+            cid.write_bytes(&mut node)
+                .expect("writing to a vec never fails");
+            Cid::new_v1(codec, hasher(&node))
+        })
+        .collect();
+    if level.is_empty() {
+        return Cid::new_v1(codec, hasher(&[]));
+    }
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    node.clear();
+                    node.push(NODE_PREFIX);
+                    left.write_bytes(&mut node)
+                        .expect("writing to a vec never fails");
+                    right
+                        .write_bytes(&mut node)
+                        .expect("writing to a vec never fails");
+                    Cid::new_v1(codec, hasher(&node))
+                }
+                _ => pair[0],
+            })
+            .collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash_codetable::{Code, MultihashDigest};
+
+    use super::root;
+    use crate::Cid;
+
+    const RAW: u64 = 0x55;
+
+    fn sha2(bytes: &[u8]) -> multihash::Multihash<64> {
+        Code::Sha2_256.digest(bytes)
+    }
+
+    fn leaf(cid: &Cid) -> Cid {
+        Cid::new_v1(RAW, sha2(&[&[0x00][..], &cid.to_bytes()].concat()))
+    }
+
+    fn node(left: &Cid, right: &Cid) -> Cid {
+        Cid::new_v1(
+            RAW,
+            sha2(&[&[0x01][..], &left.to_bytes(), &right.to_bytes()].concat()),
+        )
+    }
+
+    #[test]
+    fn tree_shape() {
+        let cids: Vec<Cid> = (0..5u8).map(|n| Cid::new_v1(RAW, sha2(&[n]))).collect();
+        let leaves: Vec<Cid> = cids.iter().map(leaf).collect();
+
+        assert_eq!(root(Vec::new(), RAW, sha2), Cid::new_v1(RAW, sha2(b"")));
+        assert_eq!(root(cids[..1].to_vec(), RAW, sha2), leaves[0]);
+        assert_eq!(
+            root(cids[..2].to_vec(), RAW, sha2),
+            node(&leaves[0], &leaves[1])
+        );
+
+        let left = node(&node(&leaves[0], &leaves[1]), &node(&leaves[2], &leaves[3]));
+        assert_eq!(root(cids.clone(), RAW, sha2), node(&left, &leaves[4]));
+
+        // The order matters.
+        let mut reversed = cids.clone();
+        reversed.reverse();
+        assert_ne!(root(reversed, RAW, sha2), root(cids, RAW, sha2));
+    }
+
+    #[test]
+    fn no_collisions() {
+        let cids: Vec<Cid> = (0..4u8).map(|n| Cid::new_v1(RAW, sha2(&[n]))).collect();
+
+        // A single CID isn't its own root.
+        assert_ne!(root(cids[..1].to_vec(), RAW, sha2), cids[0]);
+
+        // The roots of the halves, as a list, don't have the root of the whole list as root.
+        let halves = vec![
+            root(cids[..2].to_vec(), RAW, sha2),
+            root(cids[2..].to_vec(), RAW, sha2),
+        ];
+        assert_ne!(root(halves, RAW, sha2), root(cids.clone(), RAW, sha2));
+
+        // Nor do the leaves.
+        let leaves: Vec<Cid> = cids.iter().map(leaf).collect();
+        assert_ne!(root(leaves, RAW, sha2), root(cids, RAW, sha2));
+    }
+}
@@ -0,0 +1,66 @@
+//! Conversions between CIDs and multiaddr components.
+//!
+//! A `/p2p/<peer-id>` component (`/ipfs/<peer-id>` is its legacy name) identifies a peer by the
+//! multihash of its key, the corresponding CID is a CIDv1 with the `libp2p-key` codec, see
+//! [`Cid::from_peer_id`].
+use multiaddr::{Multiaddr, Protocol};
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+impl<const S: usize> Cid<S> {
+    /// Creates a CID from a `/p2p/` multiaddr component.
+    pub fn from_multiaddr_protocol(protocol: &Protocol) -> Result<Self> {
+        match protocol {
+            Protocol::P2p(peer_id) => Self::from_peer_id(peer_id),
+            _ => Err(Error::ParsingError),
+        }
+    }
+
+    /// Returns the CID of the last `/p2p/` component of a multiaddr.
+    ///
+    /// The last one is used, as it's the target of relayed addresses like
+    /// `/…/p2p/<relay>/p2p-circuit/p2p/<target>`.
+    pub fn from_multiaddr(addr: &Multiaddr) -> Result<Self> {
+        let protocol = addr
+            .iter()
+            .filter(|protocol| matches!(protocol, Protocol::P2p(_)))
+            .last()
+            .ok_or(Error::ParsingError)?;
+        Self::from_multiaddr_protocol(&protocol)
+    }
+
+    /// Returns the `/p2p/` multiaddr component of a CIDv1 with the `libp2p-key` codec.
+    pub fn to_multiaddr_protocol(&self) -> Result<Protocol<'static>> {
+        Ok(Protocol::P2p(self.to_peer_id()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multiaddr::{Multiaddr, Protocol};
+
+    use crate::Cid;
+
+    const TARGET: &str = "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA";
+    const RELAY: &str = "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N";
+
+    #[test]
+    fn components() {
+        let addr: Multiaddr = format!(
+            "/ip4/127.0.0.1/tcp/4001/p2p/{}/p2p-circuit/ipfs/{}",
+            RELAY, TARGET
+        )
+        .parse()
+        .unwrap();
+        let cid = Cid::from_multiaddr(&addr).unwrap();
+        let protocol = cid.to_multiaddr_protocol().unwrap();
+        assert_eq!(protocol.to_string(), format!("/p2p/{}", TARGET));
+        assert_eq!(Cid::from_multiaddr_protocol(&protocol).unwrap(), cid);
+
+        let addr: Multiaddr = "/ip4/127.0.0.1/tcp/4001".parse().unwrap();
+        assert!(Cid::from_multiaddr(&addr).is_err());
+        assert!(Cid::from_multiaddr_protocol(&Protocol::Tcp(4001)).is_err());
+        assert!(Cid::default().to_multiaddr_protocol().is_err());
+    }
+}
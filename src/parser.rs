@@ -0,0 +1,208 @@
+//! CID parsing restricted by an application policy.
+//!
+//! Consensus-critical applications usually only accept a few versions, codecs and hash functions.
+//! A [`CidParser`] checks those while decoding, the header is checked before the digest is read.
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::ops::RangeInclusive;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// A CID parser that only accepts CIDs matching a policy.
+///
+/// Everything is accepted unless restricted:
+///
+/// ```
+/// use cid::{CidParser, Version};
+///
+/// const RAW: u64 = 0x55;
+/// const DAG_CBOR: u64 = 0x71;
+///
+/// let parser = CidParser::new()
+///     .allow_versions([Version::V1])
+///     .allow_codecs([RAW, DAG_CBOR]);
+/// let cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+/// assert!(parser.parse_str::<64>(cid).is_ok());
+/// assert!(parser.parse_str::<64>("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CidParser {
+    versions: Vec<Version>,
+    codecs: Vec<RangeInclusive<u64>>,
+    hash_codes: Vec<u64>,
+    max_digest_len: Option<usize>,
+}
+
+impl CidParser {
+    /// Creates a parser accepting all valid CIDs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts the given versions, in addition to the already allowed ones.
+    pub fn allow_versions<I: IntoIterator<Item = Version>>(mut self, versions: I) -> Self {
+        self.versions.extend(versions);
+        self
+    }
+
+    /// Accepts the given codecs, in addition to the already allowed ones.
+    pub fn allow_codecs<I: IntoIterator<Item = u64>>(mut self, codecs: I) -> Self {
+        self.codecs
+            .extend(codecs.into_iter().map(|codec| codec..=codec));
+        self
+    }
+
+    /// Accepts a range of codecs, in addition to the already allowed ones.
+    pub fn allow_codec_range(mut self, codecs: RangeInclusive<u64>) -> Self {
+        self.codecs.push(codecs);
+        self
+    }
+
+    /// Accepts the given multihash codes, in addition to the already allowed ones.
+    pub fn allow_hash_codes<I: IntoIterator<Item = u64>>(mut self, codes: I) -> Self {
+        self.hash_codes.extend(codes);
+        self
+    }
+
+    /// Sets the maximum digest size.
+    pub fn max_digest_len(mut self, len: usize) -> Self {
+        self.max_digest_len = Some(len);
+        self
+    }
+
+    /// Checks the header of a CID.
+    ///
+    /// Fails with [`Error::InvalidCidVersion`] for a version and with [`Error::UnknownCodec`] for a
+    /// codec that isn't allowed, with [`Error::ParsingError`] for a multihash code that isn't.
+    fn check_header(&self, version: Version, codec: u64, hash_code: u64) -> Result<()> {
+        if !self.versions.is_empty() && !self.versions.contains(&version) {
+            return Err(Error::InvalidCidVersion);
+        }
+        if !self.codecs.is_empty() && !self.codecs.iter().any(|range| range.contains(&codec)) {
+            return Err(Error::UnknownCodec);
+        }
+        if !self.hash_codes.is_empty() && !self.hash_codes.contains(&hash_code) {
+            return Err(Error::ParsingError);
+        }
+        Ok(())
+    }
+
+    /// Checks that a CID matches the policy.
+    ///
+    /// A digest that is too big fails with [`Error::ParsingError`].
+    pub fn validate<const S: usize>(&self, cid: &Cid<S>) -> Result<()> {
+        self.check_header(cid.version(), cid.codec(), cid.hash().code())?;
+        match self.max_digest_len {
+            Some(max) if usize::from(cid.hash().size()) > max => Err(Error::ParsingError),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes a binary CID that matches the policy.
+    ///
+    /// The bytes must be exactly the CID, trailing bytes fail with [`Error::ParsingError`].
+    pub fn parse<const S: usize>(&self, bytes: &[u8]) -> Result<Cid<S>> {
+        let (version, codec, hash_code) = Cid::<S>::peek_header(bytes)?;
+        self.check_header(version, codec, hash_code)?;
+        let cid = Cid::try_from(bytes)?;
+        if cid.encoded_len() != bytes.len() {
+            return Err(Error::ParsingError);
+        }
+        self.validate(&cid)?;
+        Ok(cid)
+    }
+
+    /// Parses a CID string that matches the policy.
+    ///
+    /// The string must be exactly the CID, see [`Cid::parse_strict`].
+    pub fn parse_str<const S: usize>(&self, s: &str) -> Result<Cid<S>> {
+        let cid = Cid::parse_strict(s)?;
+        self.validate(&cid)?;
+        Ok(cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::*;
+
+    const RAW: u64 = 0x55;
+    const DAG_CBOR: u64 = 0x71;
+
+    fn cid(version: Version, codec: u64, code: u64, len: usize) -> Cid<64> {
+        let hash = Multihash::wrap(code, &[7; 64][..len]).unwrap();
+        Cid::new(version, codec, hash).unwrap()
+    }
+
+    #[test]
+    fn policy() {
+        let parser = CidParser::new()
+            .allow_versions([Version::V1])
+            .allow_codecs([RAW, DAG_CBOR])
+            .allow_codec_range(0x300000..=0x3fffff)
+            .allow_hash_codes([0x12, 0x1e])
+            .max_digest_len(32);
+
+        for ok in [
+            cid(Version::V1, RAW, 0x12, 32),
+            cid(Version::V1, 0x300001, 0x1e, 20),
+        ] {
+            assert_eq!(parser.parse::<64>(&ok.to_bytes()).unwrap(), ok);
+            assert_eq!(parser.parse_str::<64>(&ok.to_string()).unwrap(), ok);
+        }
+
+        let v0 = cid(Version::V0, 0x70, 0x12, 32);
+        assert!(matches!(
+            parser.parse::<64>(&v0.to_bytes()),
+            Err(Error::InvalidCidVersion)
+        ));
+        let dag_pb = cid(Version::V1, 0x70, 0x12, 32);
+        assert!(matches!(
+            parser.parse::<64>(&dag_pb.to_bytes()),
+            Err(Error::UnknownCodec)
+        ));
+        let sha2_512 = cid(Version::V1, RAW, 0x13, 32);
+        assert!(parser.parse::<64>(&sha2_512.to_bytes()).is_err());
+        let long = cid(Version::V1, RAW, 0x12, 64);
+        assert!(parser.validate(&long).is_err());
+        assert!(parser.parse_str::<64>(&long.to_string()).is_err());
+    }
+
+    #[test]
+    fn exact_input() {
+        let parser = CidParser::new();
+        let ok = cid(Version::V1, RAW, 0x12, 32);
+
+        let trailing = [ok.to_bytes(), vec![0]].concat();
+        assert!(matches!(
+            parser.parse::<64>(&trailing),
+            Err(Error::ParsingError)
+        ));
+        for path in [
+            format!("/ipfs/{}", ok),
+            format!("/ipfs/{}/x", ok),
+            format!("https://dweb.link/ipfs/{}", ok),
+        ] {
+            assert!(parser.parse_str::<64>(&path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn header_checked_first() {
+        // The digest is missing, but the codec is rejected before it's needed.
+        let parser = CidParser::new().allow_codecs([RAW]);
+        assert!(matches!(
+            parser.parse::<64>(&[0x01, 0x71, 0x12, 0x20]),
+            Err(Error::UnknownCodec)
+        ));
+        assert!(CidParser::new()
+            .parse::<64>(&[0x01, 0x71, 0x12, 0x20])
+            .is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! `/ipfs/<cid>/<path>` content paths.
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+/// The prefix of a content path.
+const IPFS_PREFIX: &str = "/ipfs/";
+
+/// A content path, `/ipfs/<cid>[/segment…][?query][#fragment]`.
+///
+/// The segments are normalized: empty and `.` segments are removed, `..` removes the previous
+/// segment, but never the CID. A trailing slash is kept, it marks the path as a directory.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpfsPath<const S: usize = 64> {
+    cid: Cid<S>,
+    segments: Vec<String>,
+    trailing_slash: bool,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+/// Splits off the query and the fragment.
+fn split_suffixes(s: &str) -> (&str, Option<String>, Option<String>) {
+    let (s, fragment) = match s.split_once('#') {
+        Some((s, fragment)) => (s, Some(fragment.to_string())),
+        None => (s, None),
+    };
+    let (s, query) = match s.split_once('?') {
+        Some((s, query)) => (s, Some(query.to_string())),
+        None => (s, None),
+    };
+    (s, query, fragment)
+}
+
+/// Appends the normalized segments of a path.
+///
+/// Returns whether the path ends like a directory, i.e. with `/`, `.` or `..`.
+fn push_segments(segments: &mut Vec<String>, path: &str) -> bool {
+    let trailing_slash =
+        !path.is_empty() && matches!(path.rsplit('/').next(), Some("" | "." | ".."));
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment.to_string()),
+        }
+    }
+    trailing_slash
+}
+
+impl<const S: usize> IpfsPath<S> {
+    /// Creates the path of the root of a CID.
+    pub fn new(cid: Cid<S>) -> Self {
+        Self {
+            cid,
+            segments: Vec::new(),
+            trailing_slash: false,
+            query: None,
+            fragment: None,
+        }
+    }
+
+    /// Returns the CID.
+    pub const fn cid(&self) -> &Cid<S> {
+        &self.cid
+    }
+
+    /// Returns the segments below the CID.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Returns `true` if the path ends with a slash, i.e. it's a directory.
+    pub fn has_trailing_slash(&self) -> bool {
+        self.trailing_slash
+    }
+
+    /// Returns the query without the leading `?`.
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Returns the fragment without the leading `#`.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// Returns the path of the parent, `None` for the root of the CID.
+    ///
+    /// The query and fragment are dropped.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, segments) = self.segments.split_last()?;
+        Some(Self {
+            segments: segments.to_vec(),
+            ..Self::new(self.cid)
+        })
+    }
+
+    /// Returns the path with `path` appended, treating this path as a directory.
+    ///
+    /// The query and fragment are dropped.
+    pub fn join(&self, path: &str) -> Self {
+        let mut segments = self.segments.clone();
+        let trailing_slash = match path {
+            "" => self.trailing_slash,
+            path => push_segments(&mut segments, path),
+        };
+        Self {
+            segments,
+            trailing_slash,
+            ..Self::new(self.cid)
+        }
+    }
+
+    /// Resolves a reference relative to this path, the way a browser resolves links.
+    ///
+    /// A reference starting with `/ipfs/` is absolute, one starting with `/` is relative to the
+    /// root of the CID and any other is relative to the parent of this path. If this path has a
+    /// trailing slash, it is its own parent.
+    pub fn resolve(&self, reference: &str) -> Result<Self> {
+        if reference.starts_with(IPFS_PREFIX) {
+            return reference.parse();
+        }
+        let (path, query, fragment) = split_suffixes(reference);
+        let mut segments = Vec::new();
+        if path.is_empty() {
+            segments.clone_from(&self.segments);
+        } else if !path.starts_with('/') {
+            match self.segments.split_last() {
+                Some(_) if self.trailing_slash => segments.clone_from(&self.segments),
+                Some((_, parent)) => segments.extend_from_slice(parent),
+                None => {}
+            }
+        }
+        let trailing_slash = match path {
+            "" => self.trailing_slash,
+            path => push_segments(&mut segments, path),
+        };
+        let query = match query {
+            None if path.is_empty() => self.query.clone(),
+            query => query,
+        };
+        Ok(Self {
+            cid: self.cid,
+            segments,
+            trailing_slash,
+            query,
+            fragment,
+        })
+    }
+}
+
+impl<const S: usize> From<Cid<S>> for IpfsPath<S> {
+    fn from(cid: Cid<S>) -> Self {
+        Self::new(cid)
+    }
+}
+
+impl<const S: usize> FromStr for IpfsPath<S> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (s, query, fragment) = split_suffixes(s);
+        let s = s.strip_prefix(IPFS_PREFIX).ok_or(Error::ParsingError)?;
+        // The path keeps its leading slash, so that a trailing slash after the CID is detected.
+        let (cid, path) = s.split_at(s.find('/').unwrap_or(s.len()));
+        if cid.is_empty() {
+            return Err(Error::ParsingError);
+        }
+        let mut segments = Vec::new();
+        let trailing_slash = push_segments(&mut segments, path);
+        Ok(Self {
+            cid: Cid::try_from(cid)?,
+            segments,
+            trailing_slash,
+            query,
+            fragment,
+        })
+    }
+}
+
+impl<const S: usize> TryFrom<&str> for IpfsPath<S> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl<const S: usize> fmt::Display for IpfsPath<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", IPFS_PREFIX, self.cid)?;
+        for segment in &self.segments {
+            write!(f, "/{}", segment)?;
+        }
+        if self.trailing_slash {
+            f.write_str("/")?;
+        }
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IpfsPath;
+
+    const CID: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+
+    #[test]
+    fn parse() {
+        let path: IpfsPath = format!("/ipfs/{}//a/./b/../c/?x=1#y", CID).parse().unwrap();
+        assert_eq!(path.cid().to_string(), CID);
+        assert_eq!(path.segments(), ["a", "c"]);
+        assert_eq!(path.query(), Some("x=1"));
+        assert_eq!(path.fragment(), Some("y"));
+        assert!(path.has_trailing_slash());
+        assert_eq!(path.to_string(), format!("/ipfs/{}/a/c/?x=1#y", CID));
+        let root: IpfsPath = format!("/ipfs/{}/", CID).parse().unwrap();
+        assert_eq!(root.to_string(), format!("/ipfs/{}/", CID));
+
+        let root: IpfsPath = format!("/ipfs/{}/../..", CID).parse().unwrap();
+        assert!(root.segments().is_empty());
+        assert_eq!(root.parent(), None);
+
+        assert!("/ipfs/".parse::<IpfsPath>().is_err());
+        assert!(format!("/ipns/{}", CID).parse::<IpfsPath>().is_err());
+        assert!(CID.parse::<IpfsPath>().is_err());
+    }
+
+    #[test]
+    fn relative() {
+        let path: IpfsPath = format!("/ipfs/{}/a/b.html?x", CID).parse().unwrap();
+        let resolve = |reference: &str| path.resolve(reference).unwrap().to_string();
+        assert_eq!(resolve("c.css"), format!("/ipfs/{}/a/c.css", CID));
+        assert_eq!(resolve("../d"), format!("/ipfs/{}/d", CID));
+        assert_eq!(resolve("/e?z"), format!("/ipfs/{}/e?z", CID));
+        assert_eq!(resolve("#top"), format!("/ipfs/{}/a/b.html?x#top", CID));
+        let other = "/ipfs/bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        assert_eq!(resolve(other), other);
+
+        assert_eq!(
+            path.join("c/d").to_string(),
+            format!("/ipfs/{}/a/b.html/c/d", CID)
+        );
+        assert_eq!(
+            path.parent().unwrap().to_string(),
+            format!("/ipfs/{}/a", CID)
+        );
+    }
+
+    #[test]
+    fn relative_to_directory() {
+        let dir: IpfsPath = format!("/ipfs/{}/a/", CID).parse().unwrap();
+        let file: IpfsPath = format!("/ipfs/{}/a", CID).parse().unwrap();
+        assert_ne!(dir, file);
+        assert_eq!(
+            dir.resolve("b").unwrap().to_string(),
+            format!("/ipfs/{}/a/b", CID)
+        );
+        assert_eq!(
+            file.resolve("b").unwrap().to_string(),
+            format!("/ipfs/{}/b", CID)
+        );
+        assert_eq!(
+            dir.resolve("b/").unwrap().to_string(),
+            format!("/ipfs/{}/a/b/", CID)
+        );
+        assert_eq!(
+            dir.resolve("..").unwrap().to_string(),
+            format!("/ipfs/{}/", CID)
+        );
+        assert_eq!(
+            dir.resolve("?q").unwrap().to_string(),
+            format!("/ipfs/{}/a/?q", CID)
+        );
+        assert_eq!(dir.join("c").to_string(), format!("/ipfs/{}/a/c", CID));
+    }
+}
@@ -0,0 +1,159 @@
+//! Python bindings.
+//!
+//! The module is exposed as `cid`. To build a Python extension, e.g. with maturin, additionally
+//! enable the `pyo3/extension-module` feature.
+
+// The code generated by `#[pymethods]` converts errors into `PyErr`, also if they already are.
+#![allow(clippy::useless_conversion)]
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+
+use multibase::Base;
+use multihash::Multihash;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::cid::Cid;
+use crate::error::Error;
+use crate::version::Version;
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+/// A CID.
+#[pyclass(name = "Cid", module = "cid", frozen)]
+#[derive(Clone, Debug)]
+pub struct PyCid {
+    inner: Cid<64>,
+}
+
+impl From<Cid<64>> for PyCid {
+    fn from(inner: Cid<64>) -> Self {
+        Self { inner }
+    }
+}
+
+impl From<PyCid> for Cid<64> {
+    fn from(cid: PyCid) -> Self {
+        cid.inner
+    }
+}
+
+#[pymethods]
+impl PyCid {
+    /// Parses the string representation of a CID.
+    #[new]
+    fn new(s: &str) -> PyResult<Self> {
+        Ok(Cid::try_from(s)?.into())
+    }
+
+    /// Parses the binary representation of a CID.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(Cid::try_from(bytes)?.into())
+    }
+
+    /// Creates a CID from its components.
+    #[staticmethod]
+    fn from_components(version: u64, codec: u64, hash_code: u64, digest: &[u8]) -> PyResult<Self> {
+        let hash = Multihash::wrap(hash_code, digest).map_err(Error::from)?;
+        Ok(Cid::new(Version::try_from(version)?, codec, hash)?.into())
+    }
+
+    /// The version.
+    #[getter]
+    fn version(&self) -> u64 {
+        self.inner.version().into()
+    }
+
+    /// The codec.
+    #[getter]
+    fn codec(&self) -> u64 {
+        self.inner.codec()
+    }
+
+    /// The code of the multihash.
+    #[getter]
+    fn hash_code(&self) -> u64 {
+        self.inner.hash().code()
+    }
+
+    /// The digest of the multihash.
+    #[getter]
+    fn digest<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, self.inner.hash().digest())
+    }
+
+    /// Returns the CID converted to a CIDv1.
+    fn to_v1(&self) -> PyResult<Self> {
+        Ok(self.inner.into_v1()?.into())
+    }
+
+    /// Returns the string representation, in the base with the given multibase prefix if set.
+    #[pyo3(signature = (base = None))]
+    fn encode(&self, base: Option<char>) -> PyResult<String> {
+        match base {
+            Some(code) => {
+                let base = Base::from_code(code).map_err(Error::from)?;
+                Ok(self.inner.to_string_of_base(base)?)
+            }
+            None => Ok(self.inner.to_string()),
+        }
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.inner.to_bytes())
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Cid('{}')", self.inner)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        Hash::hash(&self.inner, &mut hasher);
+        hasher.finish()
+    }
+}
+
+/// The `cid` Python module.
+#[pymodule]
+fn cid(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCid>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PyCid;
+
+    #[test]
+    fn components() {
+        let s = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        let cid = PyCid::new(s).unwrap();
+        assert_eq!(cid.version(), 1);
+        assert_eq!(cid.codec(), 0x55);
+        assert_eq!(cid.hash_code(), 0x12);
+        assert_eq!(cid.__str__(), s);
+        assert_eq!(cid.__repr__(), format!("Cid('{}')", s));
+        assert!(cid.encode(Some('z')).unwrap().starts_with('z'));
+
+        let copy = PyCid::from_components(1, 0x55, 0x12, cid.inner.hash().digest()).unwrap();
+        assert!(copy.__eq__(&cid));
+        assert_eq!(copy.__hash__(), cid.__hash__());
+        let bytes = cid.inner.to_bytes();
+        assert!(PyCid::from_bytes(&bytes).unwrap().__eq__(&cid));
+    }
+}
@@ -0,0 +1,169 @@
+//! Random CIDs, e.g. for reproducible load tests and simulations.
+//!
+//! [`Standard`] generates CIDs of common codecs with SHA2-256 digests, [`CidDistribution`]
+//! configures the mix of versions, codecs and hashes. The digests are random bytes, they aren't the
+//! hash of any data.
+//!
+//! ```
+//! use cid::random::CidDistribution;
+//! use cid::Cid;
+//! use rand::rngs::SmallRng;
+//! use rand::{Rng, SeedableRng};
+//!
+//! let mut rng = SmallRng::seed_from_u64(42);
+//! let cid: Cid = rng.gen();
+//! let raw = CidDistribution::new().v0_probability(0.0).codecs(&[0x55]);
+//! let cid: Cid = rng.sample(raw);
+//! assert_eq!(cid.codec(), 0x55);
+//! ```
+use multihash::Multihash;
+use rand::distributions::{Distribution, Standard};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::cid::{Cid, DAG_PB, SHA2_256};
+
+/// The codecs of the default distribution: dag-pb, raw, dag-cbor and dag-json.
+const DEFAULT_CODECS: &[u64] = &[DAG_PB, 0x55, 0x71, 0x0129];
+/// The multihash codes of the default distribution.
+const DEFAULT_HASH_CODES: &[u64] = &[SHA2_256];
+/// The digest size of CIDv0.
+const V0_DIGEST_LEN: usize = 32;
+
+/// A configurable distribution of CIDs.
+///
+/// Generated CIDv1 pick their codec and multihash code uniformly from the configured ones. The
+/// digest size is capped at the size of the CID type, CIDv0 are only generated if it's at least 32.
+#[derive(Clone, Copy, Debug)]
+pub struct CidDistribution<'a> {
+    v0_probability: f64,
+    codecs: &'a [u64],
+    hash_codes: &'a [u64],
+    digest_len: usize,
+}
+
+impl Default for CidDistribution<'_> {
+    fn default() -> Self {
+        Self {
+            v0_probability: 0.1,
+            codecs: DEFAULT_CODECS,
+            hash_codes: DEFAULT_HASH_CODES,
+            digest_len: 32,
+        }
+    }
+}
+
+impl<'a> CidDistribution<'a> {
+    /// Creates the distribution also used by [`Standard`]: 10% CIDv0, CIDv1 of the dag-pb, raw,
+    /// dag-cbor and dag-json codecs, all with 32 byte SHA2-256 digests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the probability of generating a CIDv0.
+    ///
+    /// Panics if it's not between 0 and 1.
+    pub fn v0_probability(mut self, probability: f64) -> Self {
+        assert!((0.0..=1.0).contains(&probability), "Invalid probability");
+        self.v0_probability = probability;
+        self
+    }
+
+    /// Sets the codecs of CIDv1.
+    ///
+    /// Panics if there are none.
+    pub fn codecs(mut self, codecs: &'a [u64]) -> Self {
+        assert!(!codecs.is_empty(), "No codecs");
+        self.codecs = codecs;
+        self
+    }
+
+    /// Sets the multihash codes of CIDv1.
+    ///
+    /// Panics if there are none.
+    pub fn hash_codes(mut self, hash_codes: &'a [u64]) -> Self {
+        assert!(!hash_codes.is_empty(), "No multihash codes");
+        self.hash_codes = hash_codes;
+        self
+    }
+
+    /// Sets the digest size of CIDv1.
+    pub fn digest_len(mut self, digest_len: usize) -> Self {
+        self.digest_len = digest_len;
+        self
+    }
+}
+
+impl<const S: usize> Distribution<Cid<S>> for CidDistribution<'_> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cid<S> {
+        let mut digest = [0; S];
+        if S >= V0_DIGEST_LEN && rng.gen_bool(self.v0_probability) {
+            rng.fill(&mut digest[..V0_DIGEST_LEN]);
+            let hash = Multihash::wrap(SHA2_256, &digest[..V0_DIGEST_LEN])
+                .expect("S is at least the digest size");
+            return Cid::new_v0(hash).expect("The multihash is a SHA2-256 one");
+        }
+
+        let digest = &mut digest[..self.digest_len.min(S)];
+        rng.fill(digest);
+        let codec = *self.codecs.choose(rng).expect("There are codecs");
+        let code = *self
+            .hash_codes
+            .choose(rng)
+            .expect("There are multihash codes");
+        let hash = Multihash::wrap(code, digest).expect("The digest is capped at S");
+        Cid::new_v1(codec, hash)
+    }
+}
+
+impl<const S: usize> Distribution<Cid<S>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cid<S> {
+        CidDistribution::new().sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::{CidGeneric, Version};
+
+    #[test]
+    fn reproducible() {
+        let first: Vec<Cid<64>> = SmallRng::seed_from_u64(7)
+            .sample_iter(Standard)
+            .take(100)
+            .collect();
+        let second: Vec<Cid<64>> = SmallRng::seed_from_u64(7)
+            .sample_iter(Standard)
+            .take(100)
+            .collect();
+        assert_eq!(first, second);
+        assert!(first.iter().any(|cid| cid.version() == Version::V0));
+        assert!(first
+            .iter()
+            .all(|cid| DEFAULT_CODECS.contains(&cid.codec()) && cid.hash().size() == 32));
+    }
+
+    #[test]
+    fn configured() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let dist = CidDistribution::new()
+            .v0_probability(0.0)
+            .codecs(&[0x55])
+            .hash_codes(&[0x1e, 0x16])
+            .digest_len(48);
+        for _ in 0..100 {
+            let cid: Cid<64> = rng.sample(dist);
+            assert_eq!((cid.version(), cid.codec()), (Version::V1, 0x55));
+            assert!([0x1e, 0x16].contains(&cid.hash().code()));
+            assert_eq!(cid.hash().size(), 48);
+        }
+
+        let small: CidGeneric<16> = rng.sample(CidDistribution::new().v0_probability(1.0));
+        assert_eq!(small.version(), Version::V1);
+        assert_eq!(small.hash().size(), 16);
+    }
+}
@@ -0,0 +1,57 @@
+//! [redis-rs] support.
+//!
+//! CIDs are sent and read in their binary representation, so they can directly be used as keys,
+//! values and members. A value read must be exactly the CID, nothing after it.
+//!
+//! [redis-rs]: https://github.com/redis-rs/redis-rs
+use std::convert::TryFrom;
+
+use redis::{ErrorKind, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs, Value};
+
+use crate::cid::Cid;
+use crate::error::Error;
+
+impl<const S: usize> ToRedisArgs for Cid<S> {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(&self.to_bytes())
+    }
+}
+
+impl<const S: usize> FromRedisValue for Cid<S> {
+    fn from_redis_value(value: &Value) -> RedisResult<Self> {
+        let bytes = Vec::<u8>::from_redis_value(value)?;
+        let invalid =
+            |error: Error| (ErrorKind::TypeError, "Invalid CID", error.to_string()).into();
+        let cid = Cid::try_from(bytes.as_slice()).map_err(invalid)?;
+        if cid.encoded_len() != bytes.len() {
+            return Err(invalid(Error::ParsingError));
+        }
+        Ok(cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::{FromRedisValue, ToRedisArgs, Value};
+
+    use crate::Cid;
+
+    #[test]
+    fn roundtrip() {
+        let cid: Cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+            .parse()
+            .unwrap();
+        let args = cid.to_redis_args();
+        assert_eq!(args, vec![cid.to_bytes()]);
+
+        let value = Value::BulkString(args[0].clone());
+        assert_eq!(Cid::from_redis_value(&value).unwrap(), cid);
+        assert!(Cid::from_redis_value(&Value::BulkString(vec![0x01])).is_err());
+        let trailing = [cid.to_bytes(), vec![0]].concat();
+        assert!(Cid::from_redis_value(&Value::BulkString(trailing)).is_err());
+        assert!(Cid::from_redis_value(&Value::Nil).is_err());
+    }
+}
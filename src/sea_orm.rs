@@ -0,0 +1,89 @@
+//! [SeaORM] support.
+//!
+//! CIDs are stored in their binary representation, so entity fields of type [`Cid`] map to binary
+//! columns. They can also be used as primary keys, though not auto-incremented ones. When reading,
+//! the column must contain exactly the CID, nothing after it.
+//!
+//! [SeaORM]: https://www.sea-ql.org/SeaORM/
+use std::convert::TryFrom;
+
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, DbErr, QueryResult, TryFromU64, TryGetError, TryGetable, Value};
+
+use crate::cid::Cid;
+use crate::error::Error;
+
+impl<const S: usize> From<Cid<S>> for Value {
+    fn from(cid: Cid<S>) -> Self {
+        cid.to_bytes().into()
+    }
+}
+
+impl<const S: usize> Nullable for Cid<S> {
+    fn null() -> Value {
+        <Vec<u8> as Nullable>::null()
+    }
+}
+
+impl<const S: usize> ValueType for Cid<S> {
+    fn try_from(value: Value) -> Result<Self, ValueTypeErr> {
+        let bytes = <Vec<u8> as ValueType>::try_from(value)?;
+        decode_bytes(&bytes).map_err(|_| ValueTypeErr)
+    }
+
+    fn type_name() -> String {
+        "Cid".into()
+    }
+
+    fn array_type() -> ArrayType {
+        <Vec<u8> as ValueType>::array_type()
+    }
+
+    fn column_type() -> ColumnType {
+        <Vec<u8> as ValueType>::column_type()
+    }
+}
+
+impl<const S: usize> TryGetable for Cid<S> {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let bytes = <Vec<u8> as TryGetable>::try_get_by(res, index)?;
+        decode_bytes(&bytes).map_err(|error| TryGetError::DbErr(DbErr::Type(error.to_string())))
+    }
+}
+
+impl<const S: usize> TryFromU64 for Cid<S> {
+    fn try_from_u64(_: u64) -> Result<Self, DbErr> {
+        Err(DbErr::ConvertFromU64("Cid"))
+    }
+}
+
+/// Decodes a column that must be exactly a binary CID.
+fn decode_bytes<const S: usize>(bytes: &[u8]) -> Result<Cid<S>, Error> {
+    let cid: Cid<S> = TryFrom::try_from(bytes)?;
+    if cid.encoded_len() != bytes.len() {
+        return Err(Error::ParsingError);
+    }
+    Ok(cid)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sea_query::{Nullable, ValueType};
+    use sea_orm::Value;
+
+    use crate::Cid;
+
+    #[test]
+    fn value() {
+        let cid: Cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+            .parse()
+            .unwrap();
+        let value = Value::from(cid);
+        assert_eq!(value, Value::from(cid.to_bytes()));
+        assert_eq!(<Cid as ValueType>::try_from(value).unwrap(), cid);
+        assert!(<Cid as ValueType>::try_from(Value::from(vec![0x01])).is_err());
+        let trailing = [cid.to_bytes(), vec![0]].concat();
+        assert!(<Cid as ValueType>::try_from(Value::from(trailing)).is_err());
+        assert!(<Cid as ValueType>::try_from(<Cid as Nullable>::null()).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! A sorted, prefix-compressed list of CIDs.
+//!
+//! Sorted binary CIDs share long prefixes (version, codec and multihash code), so storing only
+//! the part that differs from the previous entry (front coding) shrinks the list considerably.
+//! Every 16th entry is stored in full, so that lookups can binary search over
+//! those and only need to scan a few entries.
+//!
+//! The entries are sorted by their binary representation, which is the order used by e.g. CARv2
+//! indexes. It is not the same order as the one of the [`Ord`] implementation of [`Cid`].
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+
+use unsigned_varint::{decode as varint_decode, encode as varint_encode};
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+
+/// Every this many entries an entry is stored in full.
+const RESTART_INTERVAL: usize = 16;
+
+/// A sorted, prefix-compressed list of CIDs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SortedCidList<const S: usize = 64> {
+    /// The encoded entries.
+    data: Vec<u8>,
+    /// The offsets of the entries that are stored in full.
+    restarts: Vec<usize>,
+    /// The number of entries.
+    len: usize,
+}
+
+impl<const S: usize> Default for SortedCidList<S> {
+    fn default() -> Self {
+        Self {
+            data: Vec::new(),
+            restarts: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<const S: usize> SortedCidList<S> {
+    /// Creates a list from CIDs in arbitrary order, duplicates are removed.
+    pub fn from_cids<I: IntoIterator<Item = Cid<S>>>(cids: I) -> Self {
+        let mut keys: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.to_bytes()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut list = Self::default();
+        let mut previous: &[u8] = &[];
+        for (index, key) in keys.iter().enumerate() {
+            let shared = if index % RESTART_INTERVAL == 0 {
+                list.restarts.push(list.data.len());
+                0
+            } else {
+                common_prefix_len(previous, key)
+            };
+            let mut buf = varint_encode::usize_buffer();
+            list.data
+                .extend_from_slice(varint_encode::usize(shared, &mut buf));
+            list.data
+                .extend_from_slice(varint_encode::usize(key.len() - shared, &mut buf));
+            list.data.extend_from_slice(&key[shared..]);
+            previous = key;
+        }
+        list.len = keys.len();
+        list
+    }
+
+    /// Parses a list from its binary representation, see [`SortedCidList::as_bytes`].
+    ///
+    /// All entries are validated, they need to be valid CIDs in strictly ascending order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut list = Self::default();
+        let mut key = Vec::new();
+        let mut previous = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let is_restart = list.len % RESTART_INTERVAL == 0;
+            if is_restart {
+                list.restarts.push(offset);
+            }
+            let (shared, next) = read_entry(bytes, offset, &mut key)?;
+            if (is_restart && shared != 0) || (list.len > 0 && key <= previous) {
+                return Err(Error::ParsingError);
+            }
+            // The whole key must be the CID, without any trailing bytes.
+            if Cid::<S>::try_from(key.as_slice())?.encoded_len() != key.len() {
+                return Err(Error::ParsingError);
+            }
+            previous.clone_from(&key);
+            offset = next;
+            list.len += 1;
+        }
+        list.data = bytes.to_vec();
+        Ok(list)
+    }
+
+    /// Returns the binary representation of the list.
+    ///
+    /// Each entry is the length of the prefix shared with the previous entry, the length of the
+    /// remaining suffix (both as unsigned varints), followed by the suffix.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the number of CIDs.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no CIDs.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the list contains the CID.
+    pub fn contains(&self, cid: &Cid<S>) -> bool {
+        self.range(cid..=cid).next().is_some()
+    }
+
+    /// Returns an iterator over all CIDs in binary order.
+    pub fn iter(&self) -> SortedCidIter<'_, S> {
+        SortedCidIter {
+            data: &self.data,
+            offset: 0,
+            key: Vec::new(),
+            end: Bound::Unbounded,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the CIDs within the given range.
+    ///
+    /// The bounds are compared by their binary representation, not by the [`Ord`] of [`Cid`].
+    pub fn range<'a, R: RangeBounds<&'a Cid<S>>>(&self, range: R) -> SortedCidIter<'_, S> {
+        let end = match range.end_bound() {
+            Bound::Included(end) => Bound::Included(end.to_bytes()),
+            Bound::Excluded(end) => Bound::Excluded(end.to_bytes()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let (offset, key) = match range.start_bound() {
+            Bound::Included(start) => self.seek(&start.to_bytes(), false),
+            Bound::Excluded(start) => self.seek(&start.to_bytes(), true),
+            Bound::Unbounded => (0, Vec::new()),
+        };
+        SortedCidIter {
+            data: &self.data,
+            offset,
+            key,
+            end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the offset of the first entry that is not below `target` and the key before it.
+    fn seek(&self, target: &[u8], excluded: bool) -> (usize, Vec<u8>) {
+        let is_below = |key: &[u8]| key < target || (excluded && key == target);
+
+        // Find the last block that starts with a key below the target.
+        let block = self.restarts.partition_point(|&restart| {
+            let mut key = Vec::new();
+            read_entry(&self.data, restart, &mut key).expect("entries are valid");
+            is_below(&key)
+        });
+        let mut offset = match block {
+            0 => 0,
+            _ => self.restarts[block - 1],
+        };
+
+        let mut key = Vec::new();
+        let mut previous = Vec::new();
+        while offset < self.data.len() {
+            let (_, next) = read_entry(&self.data, offset, &mut key).expect("entries are valid");
+            if !is_below(&key) {
+                break;
+            }
+            previous.clone_from(&key);
+            offset = next;
+        }
+        (offset, previous)
+    }
+}
+
+impl<const S: usize> FromIterator<Cid<S>> for SortedCidList<S> {
+    fn from_iter<I: IntoIterator<Item = Cid<S>>>(iter: I) -> Self {
+        Self::from_cids(iter)
+    }
+}
+
+impl<'a, const S: usize> IntoIterator for &'a SortedCidList<S> {
+    type Item = Cid<S>;
+    type IntoIter = SortedCidIter<'a, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the CIDs of a [`SortedCidList`].
+#[derive(Clone, Debug)]
+pub struct SortedCidIter<'a, const S: usize> {
+    data: &'a [u8],
+    offset: usize,
+    /// The previous key, the next entry is relative to it.
+    key: Vec<u8>,
+    end: Bound<Vec<u8>>,
+    _marker: PhantomData<Cid<S>>,
+}
+
+impl<'a, const S: usize> Iterator for SortedCidIter<'a, S> {
+    type Item = Cid<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.data.len() {
+            return None;
+        }
+        let (_, next) =
+            read_entry(self.data, self.offset, &mut self.key).expect("entries are valid");
+        let in_range = match &self.end {
+            Bound::Unbounded => true,
+            Bound::Included(end) => self.key <= *end,
+            Bound::Excluded(end) => self.key < *end,
+        };
+        if !in_range {
+            self.offset = self.data.len();
+            return None;
+        }
+        self.offset = next;
+        Some(Cid::try_from(self.key.as_slice()).expect("entries are valid CIDs"))
+    }
+}
+
+/// Returns the length of the common prefix of two byte slices.
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Reads the entry at `offset`, applying it to the previous `key`.
+///
+/// Returns the length of the shared prefix and the offset of the next entry.
+fn read_entry(data: &[u8], offset: usize, key: &mut Vec<u8>) -> Result<(usize, usize)> {
+    let entry = &data[offset..];
+    let (shared, rest) = varint_decode::usize(entry)?;
+    let (suffix_len, rest) = varint_decode::usize(rest)?;
+    if shared > key.len() {
+        return Err(Error::ParsingError);
+    }
+    let suffix = rest.get(..suffix_len).ok_or(Error::InputTooShort)?;
+    key.truncate(shared);
+    key.extend_from_slice(suffix);
+    Ok((shared, offset + entry.len() - rest.len() + suffix_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ops::Bound;
+
+    use multihash::Multihash;
+
+    use super::{SortedCidList, RESTART_INTERVAL};
+    use crate::CidGeneric;
+
+    fn cid(n: u16) -> CidGeneric<32> {
+        let mut digest = [0; 32];
+        digest[..2].copy_from_slice(&n.to_be_bytes());
+        CidGeneric::new_v1(0x55, Multihash::wrap(0x12, &digest).unwrap())
+    }
+
+    #[test]
+    fn lookups() {
+        let count = RESTART_INTERVAL as u16 * 5 + 3;
+        // Only every other CID is in the list.
+        let list: SortedCidList<32> = (0..count).rev().map(|n| cid(n * 2)).collect();
+        assert_eq!(list.len(), usize::from(count));
+        // The version, codec and multihash header are shared.
+        let raw_len = usize::from(count) * cid(0).encoded_len();
+        assert!(list.as_bytes().len() < raw_len - usize::from(count) * 2);
+
+        for n in 0..count * 2 {
+            assert_eq!(list.contains(&cid(n)), n % 2 == 0, "{}", n);
+        }
+        let all: Vec<_> = list.iter().collect();
+        assert_eq!(all, (0..count).map(|n| cid(n * 2)).collect::<Vec<_>>());
+
+        let (start, end) = (cid(33), cid(40));
+        let range: Vec<_> = list.range(&start..=&end).collect();
+        assert_eq!(range, [cid(34), cid(36), cid(38), cid(40)]);
+        let range: Vec<_> = list.range(&start..&end).collect();
+        assert_eq!(range, [cid(34), cid(36), cid(38)]);
+        let rest = list
+            .range((Bound::Excluded(&end), Bound::Unbounded))
+            .count();
+        assert_eq!(rest, usize::from(count) - 21);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let list: SortedCidList<32> = (0..100).map(cid).collect();
+        let parsed = SortedCidList::<32>::from_bytes(list.as_bytes()).unwrap();
+        assert_eq!(parsed, list);
+
+        let bytes = list.as_bytes();
+        assert!(SortedCidList::<32>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // A single entry, once without and once with a trailing byte after the CID.
+        let key = cid(0).to_bytes();
+        let entry = [vec![0, key.len() as u8], key.clone()].concat();
+        assert!(SortedCidList::<32>::from_bytes(&entry).is_ok());
+        let key = [key, vec![0]].concat();
+        let entry = [vec![0, key.len() as u8], key].concat();
+        assert!(SortedCidList::<32>::from_bytes(&entry).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! [sqlx] support for Postgres and SQLite.
+//!
+//! CIDs are stored in their binary representation (`BYTEA`/`BLOB`). Columns containing the string
+//! representation (`TEXT`) can be decoded as well. In both cases the column must contain exactly
+//! the CID, nothing before or after it.
+use std::convert::TryFrom;
+
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type, TypeInfo, ValueRef};
+
+use crate::cid::Cid;
+use crate::error::{self, Error};
+
+impl<DB: Database, const S: usize> Type<DB> for Cid<S>
+where
+    Vec<u8>: Type<DB>,
+    String: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <Vec<u8> as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <Vec<u8> as Type<DB>>::compatible(ty) || <String as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database, const S: usize> Encode<'q, DB> for Cid<S>
+where
+    Vec<u8>: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.to_bytes().encode(buf)
+    }
+}
+
+impl<'r, DB: Database, const S: usize> Decode<'r, DB> for Cid<S>
+where
+    &'r [u8]: Decode<'r, DB>,
+    &'r str: Decode<'r, DB>,
+    String: Type<DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let ty = value.type_info();
+        if !ty.is_null() && <String as Type<DB>>::compatible(&ty) {
+            let s = <&str as Decode<DB>>::decode(value)?;
+            Ok(decode_str(s)?)
+        } else {
+            let bytes = <&[u8] as Decode<DB>>::decode(value)?;
+            Ok(decode_bytes(bytes)?)
+        }
+    }
+}
+
+/// Decodes a column that must be exactly a CID string, not e.g. a path containing one.
+fn decode_str<const S: usize>(s: &str) -> error::Result<Cid<S>> {
+    Cid::parse_strict(s)
+}
+
+/// Decodes a column that must be exactly a binary CID.
+fn decode_bytes<const S: usize>(bytes: &[u8]) -> error::Result<Cid<S>> {
+    let cid = Cid::try_from(bytes)?;
+    if cid.encoded_len() != bytes.len() {
+        return Err(Error::ParsingError);
+    }
+    Ok(cid)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::postgres::PgTypeInfo;
+    use sqlx::sqlite::Sqlite;
+    use sqlx::{Postgres, Type};
+
+    use super::{decode_bytes, decode_str};
+    use crate::Cid;
+
+    #[test]
+    fn decode_exact() {
+        let cid: Cid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+            .parse()
+            .unwrap();
+        assert_eq!(decode_bytes::<64>(&cid.to_bytes()).unwrap(), cid);
+        let trailing = [cid.to_bytes(), vec![0]].concat();
+        assert!(decode_bytes::<64>(&trailing).is_err());
+
+        assert_eq!(decode_str::<64>(&cid.to_string()).unwrap(), cid);
+        assert!(decode_str::<64>(&format!("/ipfs/{}", cid)).is_err());
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(
+            <Cid as Type<Postgres>>::type_info(),
+            PgTypeInfo::with_name("BYTEA")
+        );
+        assert!(<Cid as Type<Postgres>>::compatible(&PgTypeInfo::with_name(
+            "TEXT"
+        )));
+        assert!(!<Cid as Type<Postgres>>::compatible(
+            &PgTypeInfo::with_name("INT8")
+        ));
+        assert_eq!(
+            <Cid as Type<Sqlite>>::type_info(),
+            <Vec<u8> as Type<Sqlite>>::type_info()
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! CID test vectors.
+//!
+//! The vectors are JSON files in a format specific to this crate, the fixtures in `tests/fixtures`
+//! use it. A file is an array of objects with a `name`, the string `input` and, for valid CIDs, the
+//! `expected` fields. Vectors without `expected` fields must fail to parse. The `digest` is hex
+//! encoded and compared case-insensitively.
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "CIDv1 raw sha2-256",
+//!     "input": "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy",
+//!     "expected": {
+//!       "version": 1,
+//!       "codec": 85,
+//!       "hash_code": 18,
+//!       "digest": "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+//!     }
+//!   },
+//!   { "name": "empty", "input": "" }
+//! ]
+//! ```
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use multibase::Base;
+use serde::Deserialize;
+
+use crate::cid::Cid;
+
+/// A single test vector.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TestVector {
+    /// A description of the vector.
+    pub name: String,
+    /// The string representation of the CID.
+    pub input: String,
+    /// The fields of the CID, `None` if the input is invalid.
+    #[serde(default)]
+    pub expected: Option<Expected>,
+}
+
+/// The fields of a valid CID.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Expected {
+    /// The version.
+    pub version: u64,
+    /// The codec.
+    pub codec: u64,
+    /// The code of the multihash.
+    pub hash_code: u64,
+    /// The digest of the multihash, hex encoded in either case.
+    pub digest: String,
+}
+
+impl TestVector {
+    /// Parses the input and compares the result with the expected fields.
+    ///
+    /// The input must be exactly the CID, see [`Cid::parse_strict`]. Returns a description of the
+    /// mismatch on failure.
+    pub fn check(&self) -> Result<(), String> {
+        match (Cid::<64>::parse_strict(&self.input), &self.expected) {
+            (Ok(cid), Some(expected)) => {
+                let digest = Base::Base16Lower.encode(cid.hash().digest());
+                let actual = Expected {
+                    version: cid.version().into(),
+                    codec: cid.codec(),
+                    hash_code: cid.hash().code(),
+                    digest,
+                };
+                if (actual.version, actual.codec, actual.hash_code)
+                    == (expected.version, expected.codec, expected.hash_code)
+                    && actual.digest.eq_ignore_ascii_case(&expected.digest)
+                {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{}: expected {:?}, got {:?}",
+                        self.name, expected, actual
+                    ))
+                }
+            }
+            (Ok(cid), None) => Err(format!("{}: expected an error, got {:?}", self.name, cid)),
+            (Err(error), Some(_)) => Err(format!("{}: unexpected error: {}", self.name, error)),
+            (Err(_), None) => Ok(()),
+        }
+    }
+}
+
+/// Parses test vectors from JSON.
+pub fn parse(json: &str) -> serde_json::Result<Vec<TestVector>> {
+    serde_json::from_str(json)
+}
+
+/// Loads test vectors from a JSON file.
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<TestVector>> {
+    let json = fs::read_to_string(path)?;
+    parse(&json).map_err(io::Error::from)
+}
+
+/// Checks all test vectors, returning the descriptions of all failures.
+pub fn check_all<'a>(vectors: impl IntoIterator<Item = &'a TestVector>) -> Vec<String> {
+    vectors
+        .into_iter()
+        .filter_map(|vector| vector.check().err())
+        .collect()
+}
@@ -0,0 +1,101 @@
+//! [Tokio] support.
+//!
+//! CIDs can be read from an [`AsyncRead`] and written to an [`AsyncWrite`], e.g. when streaming
+//! CAR files or Bitswap messages, without buffering whole frames first.
+//!
+//! [Tokio]: https://tokio.rs
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::varint;
+
+/// Reads a varint into the buffer, returns its value.
+async fn read_varint<R: AsyncRead + Unpin>(r: &mut R, buf: &mut Vec<u8>) -> Result<u64> {
+    let start = buf.len();
+    for _ in 0..varint::MAX_LEN {
+        let byte = r.read_u8().await?;
+        buf.push(byte);
+        if byte & 0x80 == 0 {
+            return Ok(varint::peek_u64(&buf[start..])?.0);
+        }
+    }
+    Err(Error::VarIntDecodeError)
+}
+
+impl<const S: usize> Cid<S> {
+    /// Reads the bytes from an asynchronous byte stream.
+    ///
+    /// Exactly the bytes of the CID are read. The digest size is checked before the digest is
+    /// read.
+    pub async fn read_bytes_async<R: AsyncRead + Unpin>(mut r: R) -> Result<Self> {
+        let mut buf = Vec::with_capacity(S + 2 * varint::MAX_LEN);
+        let version = read_varint(&mut r, &mut buf).await?;
+        let codec = read_varint(&mut r, &mut buf).await?;
+        // CIDv0 has the fixed `0x12 0x20` prefix
+        let size = if [version, codec] == [0x12, 0x20] {
+            32
+        } else {
+            read_varint(&mut r, &mut buf).await?;
+            read_varint(&mut r, &mut buf).await?
+        };
+        if size > S as u64 {
+            return Err(Error::ParsingError);
+        }
+
+        let start = buf.len();
+        buf.resize(start + size as usize, 0);
+        r.read_exact(&mut buf[start..]).await?;
+        Self::read_bytes(&buf[..])
+    }
+
+    /// Writes the bytes to an asynchronous byte stream, returns the number of bytes written.
+    pub async fn write_bytes_async<W: AsyncWrite + Unpin>(&self, mut w: W) -> Result<usize> {
+        let bytes = self.to_bytes();
+        w.write_all(&bytes).await?;
+        Ok(bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+
+    use crate::{Cid, Error};
+
+    #[test]
+    fn roundtrip() {
+        for cid in [
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+            "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy",
+        ] {
+            let cid: Cid = cid.parse().unwrap();
+            let mut stream = Vec::new();
+            let written = block_on(cid.write_bytes_async(&mut stream)).unwrap();
+            assert_eq!(written, cid.encoded_len());
+            stream.extend_from_slice(b"next frame");
+
+            let mut reader = &stream[..];
+            assert_eq!(block_on(Cid::read_bytes_async(&mut reader)).unwrap(), cid);
+            assert_eq!(reader, b"next frame");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        // Truncated.
+        assert!(matches!(
+            block_on(Cid::read_bytes_async(&[0x01, 0x55, 0x12, 0x20, 0x00][..])),
+            Err(Error::Io(_))
+        ));
+        // A digest bigger than the CID type isn't read.
+        assert!(matches!(
+            block_on(crate::CidGeneric::<16>::read_bytes_async(
+                &[0x01, 0x55, 0x12, 0x20][..]
+            )),
+            Err(Error::ParsingError)
+        ));
+        // Non-minimal varint.
+        assert!(block_on(Cid::read_bytes_async(&[0x81, 0x00][..])).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! Optional [tracing] instrumentation, enabled with the `tracing` feature.
+//!
+//! Parse failures are emitted as debug events and slow paths as trace events, both with the `cid`
+//! target. The events only contain the length of the input, never the untrusted input itself.
+//!
+//! [tracing]: https://github.com/tokio-rs/tracing
+use crate::error::Error;
+
+/// Records the failure to parse an input of the given kind.
+///
+/// The `offset` is the number of bytes that were consumed before the failure, if known.
+#[inline]
+pub(crate) fn parse_failure(input: &'static str, error: &Error, len: usize, offset: Option<usize>) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "cid", input, ?error, len, offset, "Failed to parse CID");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (input, error, len, offset);
+}
+
+/// Records that a slow path was taken.
+#[inline]
+pub(crate) fn slow_path(path: &'static str) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(target: "cid", path, "Slow path");
+    #[cfg(not(feature = "tracing"))]
+    let _ = path;
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::convert::TryFrom;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::Cid;
+
+    /// Counts the events of the `cid` target.
+    struct Counter(Arc<AtomicUsize>);
+
+    impl Subscriber for Counter {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "cid"
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn count_events<F: FnOnce()>(f: F) -> usize {
+        let count = Arc::new(AtomicUsize::new(0));
+        tracing::subscriber::with_default(Counter(count.clone()), f);
+        count.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn parse_failures() {
+        let valid = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        assert_eq!(count_events(|| assert!(Cid::try_from(valid).is_ok())), 0);
+        assert_eq!(count_events(|| assert!(Cid::try_from("b").is_err())), 1);
+        assert_eq!(
+            count_events(|| assert!(Cid::try_from(&[1, 0x55][..]).is_err())),
+            1
+        );
+    }
+}
@@ -0,0 +1,417 @@
+//! A radix trie of CIDs, keyed by their digests.
+//!
+//! All nodes, edge labels and entries live in a few flat arrays, which are linked by index. A CID
+//! costs roughly the size of its digest plus 30 bytes, instead of the full allocated multihash of
+//! a [`Cid`] plus the overhead of a tree based set.
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use multihash::Multihash;
+
+use crate::cid::Cid;
+use crate::version::Version;
+
+/// Marks the absence of a node or entry.
+const NONE: u32 = u32::MAX;
+
+/// A node of the trie.
+#[derive(Clone, Debug)]
+struct Node {
+    /// The start of the edge label within the labels arena.
+    label_start: u32,
+    /// The length of the edge label.
+    label_len: u32,
+    /// The first child, the children are sorted by the first byte of their label.
+    first_child: u32,
+    next_sibling: u32,
+    /// The first CID whose digest ends at this node.
+    first_entry: u32,
+}
+
+/// The parts of a CID apart from the digest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Kind {
+    version: Version,
+    codec: u64,
+    hash_code: u64,
+}
+
+/// A CID whose digest ends at a node.
+#[derive(Clone, Debug)]
+struct Entry {
+    /// The index within the kinds table.
+    kind: u32,
+    next: u32,
+}
+
+/// A set of CIDs that supports queries by digest prefix.
+#[derive(Clone, Debug)]
+pub struct CidTrie<const S: usize = 64> {
+    /// All nodes, the first one is the root.
+    nodes: Vec<Node>,
+    labels: Vec<u8>,
+    entries: Vec<Entry>,
+    /// The distinct combinations of version, codec and multihash code, usually only a few.
+    kinds: Vec<Kind>,
+}
+
+impl<const S: usize> Default for CidTrie<S> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                label_start: 0,
+                label_len: 0,
+                first_child: NONE,
+                next_sibling: NONE,
+                first_entry: NONE,
+            }],
+            labels: Vec::new(),
+            entries: Vec::new(),
+            kinds: Vec::new(),
+        }
+    }
+}
+
+impl<const S: usize> CidTrie<S> {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of CIDs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the trie contains no CIDs.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds a CID, returns `false` if it was already present.
+    pub fn insert(&mut self, cid: &Cid<S>) -> bool {
+        let kind = match self.find_kind(cid) {
+            Some(kind) => kind,
+            None => {
+                self.kinds.push(kind_of(cid));
+                (self.kinds.len() - 1) as u32
+            }
+        };
+
+        let mut node = 0;
+        let mut rest = cid.hash().digest();
+        while let Some(&first) = rest.first() {
+            let (prev, child) = self.find_child(node, first);
+            if child == NONE {
+                let leaf = self.nodes.len() as u32;
+                self.nodes.push(Node {
+                    label_start: self.labels.len() as u32,
+                    label_len: rest.len() as u32,
+                    first_child: NONE,
+                    next_sibling: self.next(node, prev),
+                    first_entry: NONE,
+                });
+                self.labels.extend_from_slice(rest);
+                self.link(node, prev, leaf);
+                node = leaf;
+                break;
+            }
+
+            let common = common_prefix_len(self.label(child), rest);
+            if common < self.label(child).len() {
+                // Split the edge, the new node takes the place of the child.
+                let mid = self.nodes.len() as u32;
+                let old = &mut self.nodes[child as usize];
+                let split = Node {
+                    label_start: old.label_start,
+                    label_len: common as u32,
+                    first_child: child,
+                    next_sibling: old.next_sibling,
+                    first_entry: NONE,
+                };
+                old.label_start += common as u32;
+                old.label_len -= common as u32;
+                old.next_sibling = NONE;
+                self.nodes.push(split);
+                self.link(node, prev, mid);
+                node = mid;
+            } else {
+                node = child;
+            }
+            rest = &rest[common..];
+        }
+
+        let mut entry = self.nodes[node as usize].first_entry;
+        while entry != NONE {
+            if self.entries[entry as usize].kind == kind {
+                return false;
+            }
+            entry = self.entries[entry as usize].next;
+        }
+        self.entries.push(Entry {
+            kind,
+            next: self.nodes[node as usize].first_entry,
+        });
+        self.nodes[node as usize].first_entry = (self.entries.len() - 1) as u32;
+        true
+    }
+
+    /// Returns `true` if the trie contains the CID.
+    pub fn contains(&self, cid: &Cid<S>) -> bool {
+        let kind = match self.find_kind(cid) {
+            Some(kind) => kind,
+            None => return false,
+        };
+        let mut node = 0;
+        let mut rest = cid.hash().digest();
+        while let Some(&first) = rest.first() {
+            let (_, child) = self.find_child(node, first);
+            if child == NONE || !rest.starts_with(self.label(child)) {
+                return false;
+            }
+            rest = &rest[self.label(child).len()..];
+            node = child;
+        }
+
+        let mut entry = self.nodes[node as usize].first_entry;
+        while entry != NONE {
+            if self.entries[entry as usize].kind == kind {
+                return true;
+            }
+            entry = self.entries[entry as usize].next;
+        }
+        false
+    }
+
+    /// Returns an iterator over all CIDs, sorted by digest.
+    pub fn iter(&self) -> CidTrieIter<'_, S> {
+        self.with_prefix(&[])
+    }
+
+    /// Returns an iterator over the CIDs whose digest starts with `prefix`, sorted by digest.
+    pub fn with_prefix(&self, prefix: &[u8]) -> CidTrieIter<'_, S> {
+        let mut iter = CidTrieIter {
+            trie: self,
+            stack: Vec::new(),
+            key: Vec::new(),
+            entry: NONE,
+        };
+
+        let mut node = 0;
+        let mut rest = prefix;
+        while let Some(&first) = rest.first() {
+            let (_, child) = self.find_child(node, first);
+            if child == NONE {
+                return iter;
+            }
+            let label = self.label(child);
+            if label.starts_with(rest) {
+                // The prefix ends within the label of this child.
+                node = child;
+                break;
+            }
+            if !rest.starts_with(label) {
+                return iter;
+            }
+            iter.key.extend_from_slice(label);
+            rest = &rest[label.len()..];
+            node = child;
+        }
+        let key_len = match rest {
+            [] => iter.key.len() - self.label(node).len(),
+            _ => iter.key.len(),
+        };
+        iter.stack.push((node, key_len, false));
+        iter
+    }
+
+    fn find_kind(&self, cid: &Cid<S>) -> Option<u32> {
+        let kind = kind_of(cid);
+        self.kinds
+            .iter()
+            .position(|other| *other == kind)
+            .map(|index| index as u32)
+    }
+
+    fn label(&self, node: u32) -> &[u8] {
+        let node = &self.nodes[node as usize];
+        let start = node.label_start as usize;
+        &self.labels[start..start + node.label_len as usize]
+    }
+
+    /// Returns the child whose label starts with `byte`, and the sibling before it.
+    ///
+    /// If there is no such child, the sibling is the one it would need to be inserted after.
+    fn find_child(&self, node: u32, byte: u8) -> (u32, u32) {
+        let mut prev = NONE;
+        let mut child = self.nodes[node as usize].first_child;
+        while child != NONE {
+            let first = self.labels[self.nodes[child as usize].label_start as usize];
+            if first == byte {
+                return (prev, child);
+            }
+            if first > byte {
+                break;
+            }
+            prev = child;
+            child = self.nodes[child as usize].next_sibling;
+        }
+        (prev, NONE)
+    }
+
+    /// Returns the node that follows `prev` in the children of `node`.
+    fn next(&self, node: u32, prev: u32) -> u32 {
+        match prev {
+            NONE => self.nodes[node as usize].first_child,
+            _ => self.nodes[prev as usize].next_sibling,
+        }
+    }
+
+    /// Links `child` into the children of `node`, right after `prev`.
+    fn link(&mut self, node: u32, prev: u32, child: u32) {
+        match prev {
+            NONE => self.nodes[node as usize].first_child = child,
+            _ => self.nodes[prev as usize].next_sibling = child,
+        }
+    }
+}
+
+impl<const S: usize> Extend<Cid<S>> for CidTrie<S> {
+    fn extend<I: IntoIterator<Item = Cid<S>>>(&mut self, iter: I) {
+        for cid in iter {
+            self.insert(&cid);
+        }
+    }
+}
+
+impl<const S: usize> FromIterator<Cid<S>> for CidTrie<S> {
+    fn from_iter<I: IntoIterator<Item = Cid<S>>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<'a, const S: usize> IntoIterator for &'a CidTrie<S> {
+    type Item = Cid<S>;
+    type IntoIter = CidTrieIter<'a, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the CIDs of a [`CidTrie`].
+#[derive(Clone, Debug)]
+pub struct CidTrieIter<'a, const S: usize> {
+    trie: &'a CidTrie<S>,
+    /// The nodes still to visit, with the key length of their parent and whether their siblings
+    /// need to be visited as well.
+    stack: Vec<(u32, usize, bool)>,
+    /// The digest of the current node.
+    key: Vec<u8>,
+    /// The next entry of the current node.
+    entry: u32,
+}
+
+impl<'a, const S: usize> Iterator for CidTrieIter<'a, S> {
+    type Item = Cid<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.entry == NONE {
+            let (node, parent_len, siblings) = self.stack.pop()?;
+            let trie = self.trie;
+            let current = &trie.nodes[node as usize];
+            if siblings && current.next_sibling != NONE {
+                self.stack.push((current.next_sibling, parent_len, true));
+            }
+            self.key.truncate(parent_len);
+            self.key.extend_from_slice(trie.label(node));
+            if current.first_child != NONE {
+                self.stack.push((current.first_child, self.key.len(), true));
+            }
+            self.entry = current.first_entry;
+        }
+
+        let entry = &self.trie.entries[self.entry as usize];
+        self.entry = entry.next;
+        let kind = self.trie.kinds[entry.kind as usize];
+        let hash =
+            Multihash::wrap(kind.hash_code, &self.key).expect("digest came from a multihash");
+        Some(Cid::new(kind.version, kind.codec, hash).expect("parts came from a CID"))
+    }
+}
+
+fn kind_of<const S: usize>(cid: &Cid<S>) -> Kind {
+    Kind {
+        version: cid.version(),
+        codec: cid.codec(),
+        hash_code: cid.hash().code(),
+    }
+}
+
+/// Returns the length of the common prefix of two byte slices.
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use multihash::Multihash;
+
+    use super::CidTrie;
+    use crate::CidGeneric;
+
+    fn cid(codec: u64, digest: &[u8]) -> CidGeneric<32> {
+        CidGeneric::new_v1(codec, Multihash::wrap(0x12, digest).unwrap())
+    }
+
+    #[test]
+    fn insert_contains() {
+        let digests: [&[u8]; 6] = [b"abcd", b"abce", b"ab", b"b", b"abcdef", b""];
+        let mut trie = CidTrie::new();
+        for digest in digests {
+            assert!(trie.insert(&cid(0x55, digest)));
+        }
+        assert!(!trie.insert(&cid(0x55, b"abce")));
+        assert!(trie.insert(&cid(0x71, b"abce")));
+        assert_eq!(trie.len(), 7);
+
+        for digest in digests {
+            assert!(trie.contains(&cid(0x55, digest)));
+        }
+        assert!(trie.contains(&cid(0x71, b"abce")));
+        assert!(!trie.contains(&cid(0x71, b"abcd")));
+        for digest in [&b"a"[..], b"abc", b"abcde", b"c", b"abcdefg"] {
+            assert!(!trie.contains(&cid(0x55, digest)));
+        }
+    }
+
+    #[test]
+    fn prefix() {
+        let trie: CidTrie<32> = [&b"abd"[..], b"abcd", b"abce", b"ab", b"b", b"abcdef"]
+            .iter()
+            .map(|digest| cid(0x55, digest))
+            .collect();
+
+        let digests = |prefix: &[u8]| -> Vec<Vec<u8>> {
+            trie.with_prefix(prefix)
+                .map(|cid| cid.hash().digest().to_vec())
+                .collect()
+        };
+        assert_eq!(
+            digests(b""),
+            [&b"ab"[..], b"abcd", b"abcdef", b"abce", b"abd", b"b"]
+        );
+        assert_eq!(digests(b"abc"), [&b"abcd"[..], b"abcdef", b"abce"]);
+        assert_eq!(digests(b"abcd"), [&b"abcd"[..], b"abcdef"]);
+        assert_eq!(digests(b"abcde"), [b"abcdef"]);
+        assert_eq!(digests(b"b"), [b"b"]);
+        assert!(digests(b"abcg").is_empty());
+        assert!(digests(b"c").is_empty());
+        assert_eq!(trie.iter().count(), trie.len());
+    }
+}
@@ -0,0 +1,138 @@
+//! CIDs that were only partially validated.
+//!
+//! Re-validating CIDs that were written by the application itself is wasted work. The
+//! [`UncheckedCid`] only decodes the version and the codec of a binary CID, the multihash is
+//! validated once it is accessed.
+use core::convert::TryFrom;
+
+use multihash::Multihash;
+use unsigned_varint::decode as varint_decode;
+
+use crate::cid::{Cid, DAG_PB};
+use crate::error::{Error, Result};
+use crate::version::Version;
+
+/// A binary CID whose multihash hasn't been validated yet.
+///
+/// Only use it for CIDs coming from a trusted source, e.g. a store that only contains CIDs that
+/// were validated before they were written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UncheckedCid<'a> {
+    /// The full binary CID.
+    bytes: &'a [u8],
+    /// The version of the CID.
+    version: Version,
+    /// The codec of the CID.
+    codec: u64,
+    /// The offset at which the multihash starts.
+    hash_offset: usize,
+}
+
+impl<'a> UncheckedCid<'a> {
+    /// Decodes the version and the codec of a binary CID.
+    ///
+    /// The multihash is not validated.
+    pub fn new(bytes: &'a [u8]) -> Result<Self> {
+        // CIDv0 has the fixed `0x12 0x20` prefix
+        if Version::is_v0_binary(bytes) {
+            return Ok(Self {
+                bytes,
+                version: Version::V0,
+                codec: DAG_PB,
+                hash_offset: 0,
+            });
+        }
+
+        let (version, rest) = varint_decode::u64(bytes)?;
+        let (codec, rest) = varint_decode::u64(rest)?;
+        let version = match Version::try_from(version)? {
+            Version::V0 => return Err(Error::InvalidExplicitCidV0),
+            version => version,
+        };
+
+        Ok(Self {
+            bytes,
+            version,
+            codec,
+            hash_offset: bytes.len() - rest.len(),
+        })
+    }
+
+    /// Returns the cid version.
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the cid codec.
+    pub const fn codec(&self) -> u64 {
+        self.codec
+    }
+
+    /// Returns the full binary CID.
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the unvalidated bytes of the multihash.
+    pub fn hash_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.hash_offset..]
+    }
+
+    /// Validates and returns the multihash.
+    pub fn hash<const S: usize>(&self) -> Result<Multihash<S>> {
+        Ok(Multihash::from_bytes(self.hash_bytes())?)
+    }
+
+    /// Validates the multihash and returns the full CID.
+    pub fn to_cid<const S: usize>(&self) -> Result<Cid<S>> {
+        Cid::new(self.version, self.codec, self.hash()?)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for UncheckedCid<'a> {
+    type Error = Error;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self> {
+        Self::new(bytes)
+    }
+}
+
+impl<'a, const S: usize> TryFrom<UncheckedCid<'a>> for Cid<S> {
+    type Error = Error;
+
+    fn try_from(cid: UncheckedCid<'a>) -> Result<Self> {
+        cid.to_cid()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::UncheckedCid;
+    use crate::{Cid, Error, Version};
+
+    #[test]
+    fn deferred_validation() {
+        let cid =
+            Cid::try_from("bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4").unwrap();
+        let bytes = cid.to_bytes();
+        let unchecked = UncheckedCid::new(&bytes).unwrap();
+        assert_eq!(unchecked.version(), Version::V1);
+        assert_eq!(unchecked.codec(), cid.codec());
+        assert_eq!(unchecked.to_cid::<64>().unwrap(), cid);
+
+        // The truncated multihash is only detected once it's accessed.
+        let unchecked = UncheckedCid::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(unchecked.codec(), cid.codec());
+        assert!(matches!(unchecked.hash::<64>(), Err(Error::ParsingError)));
+    }
+
+    #[test]
+    fn v0() {
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        let bytes = cid.to_bytes();
+        let unchecked = UncheckedCid::new(&bytes).unwrap();
+        assert_eq!(unchecked.version(), Version::V0);
+        assert_eq!(unchecked.hash_bytes(), &bytes[..]);
+        assert_eq!(unchecked.to_cid::<64>().unwrap(), cid);
+    }
+}
@@ -0,0 +1,139 @@
+//! [UniFFI] bindings, e.g. for Kotlin and Swift.
+//!
+//! The bindings can be generated from the compiled library with `uniffi-bindgen`.
+//!
+//! [UniFFI]: https://mozilla.github.io/uniffi-rs/
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+
+use multibase::Base;
+use multihash::Multihash;
+
+use crate::cid::Cid as CidGeneric;
+use crate::error::Error;
+use crate::version::Version;
+
+/// The error of the bindings.
+#[derive(Debug, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum CidError {
+    /// See [`Error`], with the same description.
+    Invalid(Error),
+}
+
+impl fmt::Display for CidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<Error> for CidError {
+    fn from(error: Error) -> Self {
+        Self::Invalid(error)
+    }
+}
+
+/// A CID.
+#[derive(Debug, uniffi::Object)]
+pub struct Cid {
+    inner: CidGeneric<64>,
+}
+
+#[uniffi::export]
+impl Cid {
+    /// Parses the string representation of a CID.
+    #[uniffi::constructor]
+    pub fn parse(s: String) -> Result<Arc<Self>, CidError> {
+        Ok(Arc::new(Self {
+            inner: CidGeneric::try_from(s)?,
+        }))
+    }
+
+    /// Parses the binary representation of a CID.
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, CidError> {
+        Ok(Arc::new(Self {
+            inner: CidGeneric::try_from(bytes)?,
+        }))
+    }
+
+    /// Creates a CID from its components.
+    #[uniffi::constructor]
+    pub fn from_components(
+        version: u64,
+        codec: u64,
+        hash_code: u64,
+        digest: Vec<u8>,
+    ) -> Result<Arc<Self>, CidError> {
+        let hash = Multihash::wrap(hash_code, &digest).map_err(Error::from)?;
+        Ok(Arc::new(Self {
+            inner: CidGeneric::new(Version::try_from(version)?, codec, hash)?,
+        }))
+    }
+
+    /// Returns the version.
+    pub fn version(&self) -> u64 {
+        self.inner.version().into()
+    }
+
+    /// Returns the codec.
+    pub fn codec(&self) -> u64 {
+        self.inner.codec()
+    }
+
+    /// Returns the code of the multihash.
+    pub fn hash_code(&self) -> u64 {
+        self.inner.hash().code()
+    }
+
+    /// Returns the digest of the multihash.
+    pub fn digest(&self) -> Vec<u8> {
+        self.inner.hash().digest().to_vec()
+    }
+
+    /// Returns the binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.inner.to_bytes()
+    }
+
+    /// Returns the canonical string representation.
+    pub fn encode(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// Returns the string representation in the base with the given multibase prefix, e.g. `z`.
+    pub fn encode_with_base(&self, prefix: String) -> Result<String, CidError> {
+        let mut chars = prefix.chars();
+        let base = match (chars.next(), chars.next()) {
+            (Some(code), None) => Base::from_code(code).map_err(Error::from)?,
+            _ => return Err(Error::ParsingError.into()),
+        };
+        Ok(self.inner.to_string_of_base(base)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cid;
+
+    #[test]
+    fn components() {
+        let s = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+        let cid = Cid::parse(s.into()).unwrap();
+        assert_eq!(cid.version(), 1);
+        assert_eq!(cid.codec(), 0x55);
+        assert_eq!(cid.hash_code(), 0x12);
+        assert_eq!(cid.encode(), s);
+        assert!(cid.encode_with_base("z".into()).unwrap().starts_with('z'));
+        assert!(cid.encode_with_base("zz".into()).is_err());
+
+        let copy = Cid::from_components(1, 0x55, 0x12, cid.digest()).unwrap();
+        assert_eq!(copy.to_bytes(), cid.to_bytes());
+        assert_eq!(Cid::from_bytes(cid.to_bytes()).unwrap().encode(), s);
+        assert!(Cid::parse("foo".into()).is_err());
+        assert!(Cid::from_components(0, 0x55, 0x12, cid.digest()).is_err());
+    }
+}
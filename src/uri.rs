@@ -0,0 +1,343 @@
+//! `ipfs://` and `ipns://` URIs.
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::cid::Cid;
+use crate::error::{Error, Result};
+use crate::ipns::IpnsName;
+
+/// What a [`ContentUri`] points to.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UriRoot<const S: usize = 64> {
+    /// Immutable content, `ipfs://<cid>`.
+    Ipfs(Cid<S>),
+    /// A key-based IPNS name, `ipns://<name>`.
+    Ipns(IpnsName<S>),
+    /// A DNSLink domain, `ipns://<domain>`, lowercased.
+    Dnslink(String),
+}
+
+impl<const S: usize> fmt::Display for UriRoot<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ipfs(cid) => match cid.into_v1() {
+                Ok(cid) => cid.fmt(f),
+                Err(_) => cid.fmt(f),
+            },
+            Self::Ipns(name) => name.fmt(f),
+            Self::Dnslink(domain) => f.write_str(domain),
+        }
+    }
+}
+
+impl<const S: usize> UriRoot<S> {
+    /// Parses an IPNS name, either a key-based one or a DNSLink domain.
+    ///
+    /// A string is only treated as a domain if it isn't a valid key-based name.
+    pub fn from_ipns_name(name: &str) -> Result<Self> {
+        match name.parse() {
+            Ok(name) => Ok(Self::Ipns(name)),
+            Err(_) if is_domain(name) => Ok(Self::Dnslink(name.to_ascii_lowercase())),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Parses an `/ipns/<name>[/path]` path, returns the name and the remaining path.
+    ///
+    /// The remaining path is either empty or starts with a `/`.
+    pub fn from_ipns_path(path: &str) -> Result<(Self, &str)> {
+        let rest = path.strip_prefix("/ipns/").ok_or(Error::ParsingError)?;
+        let (name, rest) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        Ok((Self::from_ipns_name(name)?, rest))
+    }
+}
+
+/// An `ipfs://<cid>[/path][?query][#fragment]` or `ipns://<name>[/path][?query][#fragment]` URI.
+///
+/// The path, query and fragment are kept as they are, i.e. still percent-encoded. The canonical
+/// representation, used for display, has the CID as CIDv1 in base32 and IPNS names in base36,
+/// as those are safe to use in places where URIs are treated case-insensitively.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContentUri<const S: usize = 64> {
+    root: UriRoot<S>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl<const S: usize> ContentUri<S> {
+    /// Creates a URI without a path, query and fragment.
+    pub fn new(root: UriRoot<S>) -> Self {
+        Self {
+            root,
+            path: String::new(),
+            query: None,
+            fragment: None,
+        }
+    }
+
+    /// Returns what the URI points to.
+    pub fn root(&self) -> &UriRoot<S> {
+        &self.root
+    }
+
+    /// Returns the CID of an `ipfs://` URI.
+    pub fn cid(&self) -> Option<&Cid<S>> {
+        match &self.root {
+            UriRoot::Ipfs(cid) => Some(cid),
+            _ => None,
+        }
+    }
+
+    /// Returns the path, either empty or starting with a `/`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the query without the leading `?`.
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Returns the fragment without the leading `#`.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// Returns the URI with the path replaced, a missing leading `/` is added.
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path.clear();
+        if !path.is_empty() && !path.starts_with('/') {
+            self.path.push('/');
+        }
+        self.path.push_str(path);
+        self
+    }
+
+    /// Returns the URI with the query replaced.
+    pub fn with_query(mut self, query: Option<&str>) -> Self {
+        self.query = query.map(ToString::to_string);
+        self
+    }
+
+    /// Returns the URI with the fragment replaced.
+    pub fn with_fragment(mut self, fragment: Option<&str>) -> Self {
+        self.fragment = fragment.map(ToString::to_string);
+        self
+    }
+}
+
+impl<const S: usize> From<Cid<S>> for ContentUri<S> {
+    fn from(cid: Cid<S>) -> Self {
+        Self::new(UriRoot::Ipfs(cid))
+    }
+}
+
+impl<const S: usize> From<IpnsName<S>> for ContentUri<S> {
+    fn from(name: IpnsName<S>) -> Self {
+        Self::new(UriRoot::Ipns(name))
+    }
+}
+
+impl<const S: usize> Cid<S> {
+    /// Parses an `ipfs://<cid>` or a key-based `ipns://<name>` URI, returns the CID and the path.
+    ///
+    /// The CID of an IPNS name is its `libp2p-key` CIDv1, a DNSLink domain fails. The path is
+    /// either empty or starts with a `/`, query and fragment are dropped, parse a [`ContentUri`]
+    /// to keep them.
+    pub fn from_uri(uri: &str) -> Result<(Self, String)> {
+        let ContentUri { root, path, .. } = uri.parse()?;
+        let cid = match root {
+            UriRoot::Ipfs(cid) => cid,
+            UriRoot::Ipns(name) => name.into(),
+            UriRoot::Dnslink(_) => return Err(Error::ParsingError),
+        };
+        Ok((cid, path))
+    }
+}
+
+/// Returns whether the string is a plausible domain name for DNSLink.
+fn is_domain(s: &str) -> bool {
+    s.contains('.')
+        && s.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        })
+}
+
+/// Strips a case-insensitive scheme including the `://`.
+fn strip_scheme<'a>(s: &'a str, scheme: &str) -> Option<&'a str> {
+    let prefix = s.get(..scheme.len() + 3)?;
+    if prefix[..scheme.len()].eq_ignore_ascii_case(scheme) && &prefix[scheme.len()..] == "://" {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+impl<const S: usize> FromStr for ContentUri<S> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (s, fragment) = match s.split_once('#') {
+            Some((s, fragment)) => (s, Some(fragment.to_string())),
+            None => (s, None),
+        };
+        let (s, query) = match s.split_once('?') {
+            Some((s, query)) => (s, Some(query.to_string())),
+            None => (s, None),
+        };
+        let (ipfs, rest) = match (strip_scheme(s, "ipfs"), strip_scheme(s, "ipns")) {
+            (Some(rest), _) => (true, rest),
+            (_, Some(rest)) => (false, rest),
+            _ => return Err(Error::ParsingError),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let root = if ipfs {
+            if authority.is_empty() {
+                return Err(Error::ParsingError);
+            }
+            UriRoot::Ipfs(Cid::try_from(authority)?)
+        } else {
+            UriRoot::from_ipns_name(authority)?
+        };
+        Ok(Self {
+            root,
+            path: path.to_string(),
+            query,
+            fragment,
+        })
+    }
+}
+
+impl<const S: usize> TryFrom<&str> for ContentUri<S> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl<const S: usize> fmt::Display for ContentUri<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scheme = match self.root {
+            UriRoot::Ipfs(_) => "ipfs",
+            UriRoot::Ipns(_) | UriRoot::Dnslink(_) => "ipns",
+        };
+        write!(f, "{}://{}{}", scheme, self.root, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cid, ContentUri, UriRoot};
+
+    #[test]
+    fn ipfs() {
+        let uri: ContentUri = "ipfs://QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n/a/b%20c?x=1#y"
+            .parse()
+            .unwrap();
+        let cid = Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        assert_eq!(uri.cid(), Some(&cid));
+        assert_eq!(uri.path(), "/a/b%20c");
+        assert_eq!(uri.query(), Some("x=1"));
+        assert_eq!(uri.fragment(), Some("y"));
+        assert_eq!(
+            uri.to_string(),
+            "ipfs://bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku/a/b%20c?x=1#y"
+        );
+        let canonical: ContentUri = uri.to_string().parse().unwrap();
+        assert_eq!(canonical.to_string(), uri.to_string());
+
+        let uri = ContentUri::from(cid).with_path("index.html");
+        assert_eq!(uri.path(), "/index.html");
+        assert!("ipfs://".parse::<ContentUri>().is_err());
+        assert!(
+            "https://ipfs.io/ipfs/QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"
+                .parse::<ContentUri>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn ipns() {
+        let uri: ContentUri = "IPNS://12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA/"
+            .parse()
+            .unwrap();
+        assert!(matches!(uri.root(), UriRoot::Ipns(_)));
+        assert_eq!(uri.cid(), None);
+        assert_eq!(
+            uri.to_string(),
+            "ipns://k51qzi5uqu5dhdmyb9bd18pypu2wp5lpv2xnskfmrqa4lb5knqryrotb05e7or/"
+        );
+
+        let uri: ContentUri = "ipns://En.Wikipedia-on-IPFS.org/wiki".parse().unwrap();
+        assert_eq!(
+            uri.root(),
+            &UriRoot::Dnslink("en.wikipedia-on-ipfs.org".into())
+        );
+        assert_eq!(uri.to_string(), "ipns://en.wikipedia-on-ipfs.org/wiki");
+        assert!("ipns://localhost".parse::<ContentUri>().is_err());
+    }
+
+    #[test]
+    fn ipns_path() {
+        let (root, rest) = UriRoot::<64>::from_ipns_path(
+            "/ipns/12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA/a/b",
+        )
+        .unwrap();
+        assert_eq!(
+            root.to_string(),
+            "k51qzi5uqu5dhdmyb9bd18pypu2wp5lpv2xnskfmrqa4lb5knqryrotb05e7or"
+        );
+        assert_eq!(rest, "/a/b");
+
+        let (root, rest) = UriRoot::<64>::from_ipns_path("/ipns/Docs.IPFS.tech").unwrap();
+        assert_eq!(root, UriRoot::Dnslink("docs.ipfs.tech".into()));
+        assert_eq!(rest, "");
+
+        assert!(UriRoot::<64>::from_ipns_path("/ipns/localhost").is_err());
+        assert!(UriRoot::<64>::from_ipns_path("/ipns/").is_err());
+        assert!(UriRoot::<64>::from_ipns_path("/ipfs/docs.ipfs.tech").is_err());
+    }
+
+    #[test]
+    fn cid_from_uri() {
+        let (cid, path) =
+            Cid::from_uri("ipfs://QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n/a/b?x=1").unwrap();
+        assert_eq!(
+            cid,
+            Cid::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap()
+        );
+        assert_eq!(path, "/a/b");
+
+        let (cid, path) =
+            Cid::from_uri("ipns://k51qzi5uqu5dhdmyb9bd18pypu2wp5lpv2xnskfmrqa4lb5knqryrotb05e7or")
+                .unwrap();
+        assert_eq!(cid.codec(), crate::LIBP2P_KEY);
+        assert_eq!(path, "");
+
+        assert!(Cid::from_uri("ipns://en.wikipedia-on-ipfs.org/wiki").is_err());
+        assert!(Cid::from_uri("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").is_err());
+    }
+}
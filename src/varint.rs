@@ -0,0 +1,138 @@
+//! Unsigned varints, as used by CIDs and many block formats.
+//!
+//! Only minimal encodings are accepted, as required by the [multiformats spec], and all errors are
+//! this crate's [`Error`].
+//!
+//! [multiformats spec]: https://github.com/multiformats/unsigned-varint
+use unsigned_varint::{decode, encode};
+
+use crate::error::{Error, Result};
+use crate::trace;
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use core2::io;
+
+/// The maximum length of an encoded `u64`.
+pub const MAX_LEN: usize = 10;
+
+/// Reads a single byte, an exhausted reader is an error.
+#[inline]
+fn read_u8<R: io::Read>(r: &mut R) -> Result<u8> {
+    let mut byte = [0u8];
+    if r.read(&mut byte)? == 0 {
+        #[cfg(feature = "std")]
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        #[cfg(not(feature = "std"))]
+        return Err(Error::VarIntDecodeError);
+    }
+    Ok(byte[0])
+}
+
+/// Reads a varint, the bytes after it are left in the reader.
+///
+/// Fails with [`Error::VarIntDecodeError`] if the encoding isn't minimal or the value doesn't fit
+/// into a `u64`. If the reader ends before the varint does, it fails with an [`Error::Io`] of kind
+/// `UnexpectedEof` with the `std` feature, and with [`Error::VarIntDecodeError`] without it.
+///
+/// Versions and codecs are almost always encoded in one or two bytes, hence those are decoded
+/// directly before falling back to the general loop.
+#[inline]
+pub fn read_u64<R: io::Read>(mut r: R) -> Result<u64> {
+    let mut b = encode::u64_buffer();
+
+    b[0] = read_u8(&mut r)?;
+    if decode::is_last(b[0]) {
+        return Ok(u64::from(b[0]));
+    }
+    b[1] = read_u8(&mut r)?;
+    if decode::is_last(b[1]) {
+        // A trailing zero byte is not a minimal encoding.
+        if b[1] == 0 {
+            return Err(Error::VarIntDecodeError);
+        }
+        return Ok(u64::from(b[0] & 0x7f) | u64::from(b[1]) << 7);
+    }
+
+    trace::slow_path("varint longer than two bytes");
+    for i in 2..b.len() {
+        b[i] = read_u8(&mut r)?;
+        if decode::is_last(b[i]) {
+            match decode::u64(&b[..=i]) {
+                Ok((value, _)) => return Ok(value),
+                Err(_) => return Err(Error::VarIntDecodeError),
+            }
+        }
+    }
+    Err(Error::VarIntDecodeError)
+}
+
+/// Decodes the varint at the start of the bytes without consuming them.
+///
+/// Returns the value and the length of its encoding.
+pub fn peek_u64(bytes: &[u8]) -> Result<(u64, usize)> {
+    let (value, rest) = decode::u64(bytes)?;
+    Ok((value, bytes.len() - rest.len()))
+}
+
+/// Writes a varint, returns the number of bytes written.
+pub fn write_u64<W: io::Write>(mut w: W, value: u64) -> Result<usize> {
+    let mut buf = encode::u64_buffer();
+    let encoded = encode::u64(value, &mut buf);
+    w.write_all(encoded)?;
+    Ok(encoded.len())
+}
+
+/// Returns the length of the encoding of a value.
+pub const fn encoded_len(value: u64) -> usize {
+    let mut len = 1;
+    let mut value = value >> 7;
+    while value != 0 {
+        len += 1;
+        value >>= 7;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_u64() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, 0x1f_ffff, u64::MAX] {
+            let mut buf = encode::u64_buffer();
+            let bytes = encode::u64(value, &mut buf);
+            assert_eq!(read_u64(bytes).unwrap(), value);
+            assert_eq!(peek_u64(bytes).unwrap(), (value, bytes.len()));
+            assert_eq!(encoded_len(value), bytes.len());
+        }
+        // Non-minimal encodings are rejected.
+        assert!(read_u64(&[0x81, 0x00][..]).is_err());
+        assert!(read_u64(&[0x81, 0x80, 0x00][..]).is_err());
+        assert!(peek_u64(&[0x81, 0x00]).is_err());
+        // Not enough bytes.
+        assert!(peek_u64(&[0x81]).is_err());
+        #[cfg(feature = "std")]
+        assert!(matches!(
+            read_u64(&[0x81][..]),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        #[cfg(not(feature = "std"))]
+        assert!(matches!(
+            read_u64(&[0x81][..]),
+            Err(Error::VarIntDecodeError)
+        ));
+    }
+
+    #[test]
+    fn test_write_u64() {
+        let mut buf = [0; MAX_LEN];
+        assert_eq!(write_u64(&mut buf[..], 0x4000).unwrap(), 3);
+        assert_eq!(peek_u64(&buf).unwrap(), (0x4000, 3));
+        assert_eq!(write_u64(&mut buf[..], u64::MAX).unwrap(), MAX_LEN);
+        assert!(write_u64(&mut buf[..2], 0x4000).is_err());
+    }
+}
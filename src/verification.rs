@@ -0,0 +1,71 @@
+//! [Kani] proof harnesses for the decoding paths.
+//!
+//! Kani checks the harnesses for all inputs up to the given size, the absence of panics and of
+//! undefined behaviour is checked implicitly. Run them with `cargo kani`.
+//!
+//! [Kani]: https://github.com/model-checking/kani
+use core::convert::TryFrom;
+
+use unsigned_varint::encode as varint_encode;
+
+use crate::cid::Cid;
+use crate::varint;
+
+/// The maximum size of an encoded varint.
+const MAX_VARINT_LEN: usize = 10;
+/// The maximum input size for decoding CIDs.
+const MAX_CID_LEN: usize = 40;
+/// The maximum digest size of the CIDs, smaller than the input so that overflows are covered.
+const MAX_DIGEST_LEN: usize = 32;
+
+/// Returns a slice of arbitrary bytes of arbitrary length up to `N`.
+fn any_slice<const N: usize>(bytes: &[u8; N]) -> &[u8] {
+    let len: usize = kani::any();
+    kani::assume(len <= N);
+    &bytes[..len]
+}
+
+#[kani::proof]
+#[kani::unwind(11)]
+fn varint_read_u64_never_panics() {
+    let bytes: [u8; MAX_VARINT_LEN] = kani::any();
+    let mut input = any_slice(&bytes);
+    let len = input.len();
+    if let Ok(value) = varint::read_u64(&mut input) {
+        // Only minimal encodings are accepted, hence decoding is the inverse of encoding.
+        let mut buf = varint_encode::u64_buffer();
+        let encoded = varint_encode::u64(value, &mut buf);
+        assert_eq!(encoded, &bytes[..len - input.len()]);
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(11)]
+fn varint_roundtrip() {
+    let value: u64 = kani::any();
+    let mut buf = varint_encode::u64_buffer();
+    let mut encoded: &[u8] = varint_encode::u64(value, &mut buf);
+    assert_eq!(varint::read_u64(&mut encoded).unwrap(), value);
+    assert!(encoded.is_empty());
+}
+
+#[kani::proof]
+#[kani::unwind(41)]
+fn read_bytes_never_panics() {
+    let bytes: [u8; MAX_CID_LEN] = kani::any();
+    let _ = Cid::<MAX_DIGEST_LEN>::read_bytes(any_slice(&bytes));
+}
+
+#[kani::proof]
+#[kani::unwind(41)]
+fn read_bytes_roundtrip() {
+    let bytes: [u8; MAX_CID_LEN] = kani::any();
+    let input = any_slice(&bytes);
+    if let Ok(cid) = Cid::<MAX_DIGEST_LEN>::read_bytes(input) {
+        let mut buf = [0; MAX_CID_LEN];
+        let written = cid.write_bytes(&mut buf[..]).unwrap();
+        assert_eq!(written, cid.encoded_len());
+        assert_eq!(&buf[..written], &input[..written]);
+        assert_eq!(Cid::try_from(&buf[..written]).unwrap(), cid);
+    }
+}
@@ -6,7 +6,7 @@ mod std_tests {
     use std::convert::{TryFrom, TryInto};
     use std::str::FromStr;
 
-    use cid::{Cid, CidGeneric, Error, Version};
+    use cid::{Cid, CidBuilder, CidGeneric, Error, Version};
     use multibase::Base;
     use multihash_codetable::{Code, MultihashDigest};
 
@@ -65,6 +65,7 @@ mod std_tests {
         assert!(matches!(Cid::try_from(bad), Err(Error::ParsingError)));
     }
 
+    #[cfg(feature = "ipfs-path")]
     #[test]
     fn from() {
         let the_hash = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
@@ -76,12 +77,168 @@ mod std_tests {
         ];
 
         for case in cases {
+            assert_eq!(
+                Cid::from_ipfs_path(&case).unwrap(),
+                Cid::try_from(the_hash).unwrap()
+            );
             let cid = Cid::try_from(case).unwrap();
             assert_eq!(cid.version(), Version::V0);
             assert_eq!(cid.to_string(), the_hash);
         }
     }
 
+    #[cfg(not(feature = "ipfs-path"))]
+    #[test]
+    fn from_without_ipfs_path() {
+        let the_hash = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+        let cid = Cid::try_from(the_hash).unwrap();
+
+        for case in [
+            format!("/ipfs/{:}", &the_hash),
+            format!("https://ipfs.io/ipfs/{:}", &the_hash),
+        ] {
+            assert!(Cid::try_from(case.as_str()).is_err());
+            assert!(Cid::from_str(&case).is_err());
+            assert_eq!(Cid::from_ipfs_path(&case).unwrap(), cid);
+        }
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let the_hash = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+        let cid = Cid::parse_strict(the_hash).unwrap();
+        assert_eq!(cid, Cid::try_from(the_hash).unwrap());
+        let v1 = cid.into_v1().unwrap().to_string();
+        assert_eq!(Cid::parse_strict(&v1).unwrap(), cid.into_v1().unwrap());
+
+        for case in [
+            format!("/ipfs/{}", the_hash),
+            format!("https://ipfs.io/ipfs/{}", v1),
+        ] {
+            assert!(Cid::from_ipfs_path(&case).is_ok());
+            assert!(Cid::parse_strict(&case).is_err());
+        }
+        assert!(Cid::parse_strict(&format!("{}/", v1)).is_err());
+        assert!(Cid::parse_strict(&format!(" {}", the_hash)).is_err());
+    }
+
+    #[test]
+    fn test_from_str_with_base() {
+        let v0 = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+        let (cid, base) = Cid::from_str_with_base(v0).unwrap();
+        assert_eq!(cid.to_string(), v0);
+        assert_eq!(base, None);
+
+        let v1 = cid.into_v1().unwrap();
+        for base in [
+            Base::Base32Lower,
+            Base::Base36Lower,
+            Base::Base58Btc,
+            Base::Base64Url,
+        ] {
+            let encoded = v1.to_string_of_base(base).unwrap();
+            assert_eq!(Cid::from_str_with_base(&encoded).unwrap(), (v1, Some(base)));
+        }
+        assert!(Cid::from_str_with_base(&format!("/ipfs/{}", v0)).is_err());
+    }
+
+    #[test]
+    fn test_case_insensitive_bases() {
+        let cid =
+            Cid::from_str("bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy").unwrap();
+        for base in [
+            Base::Base32Lower,
+            Base::Base32Upper,
+            Base::Base36Lower,
+            Base::Base36Upper,
+            Base::Base16Lower,
+            Base::Base16Upper,
+        ] {
+            let encoded = cid.to_string_of_base(base).unwrap();
+            let (prefix, rest) = encoded.split_at(1);
+            for mixed in [
+                format!("{}{}", prefix.to_uppercase(), rest.to_lowercase()),
+                format!("{}{}", prefix.to_lowercase(), rest.to_uppercase()),
+            ] {
+                assert_eq!(Cid::parse_strict(&mixed).unwrap(), cid);
+            }
+        }
+
+        let v0 = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+        assert!(Cid::parse_strict(&v0.to_lowercase()).is_err());
+    }
+
+    #[test]
+    fn test_to_string_base36() {
+        let v0 = Cid::from_str("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        let v1 = v0.into_v1().unwrap();
+        let base36 = v1.to_string_of_base(Base::Base36Lower).unwrap();
+        assert!(base36.starts_with('k'));
+        assert_eq!(v0.to_string_base36(), base36);
+        assert_eq!(v1.to_string_base36(), base36);
+        assert_eq!(Cid::from_str(&base36).unwrap(), v1);
+    }
+
+    #[test]
+    fn test_parse_canonical() {
+        let v0 = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+        let v1 = "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+        for canonical in [v0, v1] {
+            assert!(Cid::is_canonical_str(canonical));
+            assert_eq!(
+                Cid::parse_canonical(canonical).unwrap(),
+                Cid::from_str(canonical).unwrap()
+            );
+        }
+
+        let cid = Cid::from_str(v1).unwrap();
+        for other in [
+            v1.to_uppercase(),
+            cid.to_string_of_base(Base::Base58Btc).unwrap(),
+            cid.to_string_base36(),
+        ] {
+            assert!(Cid::from_str(&other).is_ok());
+            assert!(!Cid::is_canonical_str(&other));
+            assert!(matches!(
+                Cid::parse_canonical(&other),
+                Err(Error::NotCanonical)
+            ));
+        }
+        // The version and codec varints aren't minimal.
+        let mut bytes = vec![0x81, 0x00, 0xf0, 0x00];
+        bytes.extend_from_slice(&cid.hash().to_bytes());
+        let non_minimal = multibase::encode(Base::Base32Lower, bytes);
+        assert!(!Cid::is_canonical_str(&non_minimal));
+        assert!(!matches!(
+            Cid::parse_canonical("not a cid"),
+            Err(Error::NotCanonical)
+        ));
+    }
+
+    #[test]
+    fn test_write_str_of_base() {
+        let v0 = Cid::from_str("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").unwrap();
+        let v1 = v0.into_v1().unwrap();
+        for (cid, base) in [
+            (v0, Base::Base58Btc),
+            (v1, Base::Base32Lower),
+            (v1, Base::Base32Upper),
+            (v1, Base::Base36Lower),
+            (v1, Base::Base64),
+        ] {
+            let mut written = String::from("prefix:");
+            cid.write_str_of_base(base, &mut written).unwrap();
+            assert_eq!(
+                written,
+                format!("prefix:{}", cid.to_string_of_base(base).unwrap())
+            );
+        }
+        assert!(matches!(
+            v0.write_str_of_base(Base::Base32Lower, &mut String::new()),
+            Err(Error::InvalidCidV0Base)
+        ));
+    }
+
     #[test]
     fn test_hash() {
         let data: Vec<u8> = vec![1, 2, 3];
@@ -92,6 +249,45 @@ mod std_tests {
         assert_eq!(&data, map.get(&cid).unwrap());
     }
 
+    #[test]
+    fn test_fast_cid_map() {
+        use cid::hasher::FastCidMap;
+
+        let data: Vec<u8> = vec![1, 2, 3];
+        let mut map = FastCidMap::default();
+        let cid_v0 = Cid::new_v0(Code::Sha2_256.digest(&data)).unwrap();
+        let cid_v1 = Cid::new_v1(RAW, Code::Sha2_256.digest(&data));
+        map.insert(cid_v0, 0);
+        map.insert(cid_v1, 1);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&cid_v0), Some(&0));
+        assert_eq!(map.get(&cid_v1), Some(&1));
+    }
+
+    #[test]
+    fn test_ordering() {
+        use std::cmp::Ordering;
+
+        let hash = Code::Sha2_256.digest(b"foo");
+        let truncated = multihash::Multihash::wrap(hash.code(), &hash.digest()[..20]).unwrap();
+        // The truncated multihash still contains the rest of the digest in its allocated space.
+        let cid = Cid::new_v1(RAW, hash.truncate(20));
+        let cid_truncated = Cid::new_v1(RAW, truncated);
+        assert_eq!(cid, cid_truncated);
+        assert_eq!(cid.cmp(&cid_truncated), Ordering::Equal);
+
+        let mut cids = [
+            Cid::new_v1(RAW, Code::Sha2_256.digest(b"bar")),
+            Cid::new_v1(DAG_PB, hash),
+            Cid::new_v1(RAW, hash),
+            Cid::new_v0(hash).unwrap(),
+        ];
+        cids.sort();
+        assert_eq!(cids[0].version(), Version::V0);
+        assert_eq!(cids[3].codec(), DAG_PB);
+        assert!(cids[1].hash().digest() < cids[2].hash().digest());
+    }
+
     #[test]
     fn test_base32() {
         let cid =
@@ -211,6 +407,152 @@ mod std_tests {
         assert_eq!(cid_v1.version(), Version::V1);
         assert_eq!(cid_v1, cid);
     }
+
+    #[test]
+    fn test_encoded_len() {
+        // Usable for sizing fixed-capacity buffers in const contexts.
+        const fn frame_len(cid: &Cid) -> usize {
+            4 + cid.encoded_len()
+        }
+        let cids = [
+            Cid::from_str("QmTPcW343HGMdoxarwvHHoPhkbo5GfNYjnZkyW5DBtpvLe").unwrap(),
+            Cid::new_v1(RAW, Code::Sha2_512.digest(b"foo")),
+            Cid::new_v1(u64::MAX, Code::Sha2_256.digest(b"foo")),
+            Cid::new_v1(0x4000, Code::Sha2_256.digest(b"foo")),
+        ];
+        for cid in cids {
+            let mut buf = Vec::new();
+            assert_eq!(cid.write_bytes(&mut buf).unwrap(), cid.encoded_len());
+            assert_eq!(buf.len() + 4, frame_len(&cid));
+        }
+    }
+
+    #[test]
+    fn test_read_bytes_with_consumed() {
+        let cid = Cid::new_v1(0x4000, Code::Sha2_256.digest(b"foo"));
+        let mut stream = cid.to_bytes();
+        stream.extend_from_slice(b"trailing payload");
+        let mut reader = &stream[..];
+        let (read, consumed) = Cid::read_bytes_with_consumed(&mut reader).unwrap();
+        assert_eq!(read, cid);
+        assert_eq!(consumed, stream.len() - reader.len());
+        assert_eq!(reader, b"trailing payload");
+
+        let v0 = Cid::from_str("QmTPcW343HGMdoxarwvHHoPhkbo5GfNYjnZkyW5DBtpvLe").unwrap();
+        let bytes = v0.to_bytes();
+        assert_eq!(Cid::read_bytes_with_consumed(&bytes[..]).unwrap(), (v0, 34));
+    }
+
+    #[test]
+    fn test_peek_header() {
+        let cid = Cid::new_v1(0x4000, Code::Sha2_512.digest(b"foo"));
+        let bytes = cid.to_bytes();
+        let mut reader = &bytes[..];
+        assert_eq!(
+            Cid::peek_header(&mut reader).unwrap(),
+            (Version::V1, 0x4000, 0x13)
+        );
+        // Only the header was consumed.
+        assert_eq!(reader.len(), 1 + 64);
+
+        let v0 = Cid::from_str("QmTPcW343HGMdoxarwvHHoPhkbo5GfNYjnZkyW5DBtpvLe").unwrap();
+        assert_eq!(
+            Cid::peek_header(&v0.to_bytes()[..]).unwrap(),
+            (Version::V0, DAG_PB, 0x12)
+        );
+        // The digest isn't needed.
+        assert!(Cid::peek_header(&bytes[..5]).is_ok());
+        assert!(matches!(
+            Cid::peek_header(&[0x00, 0x70, 0x12][..]),
+            Err(Error::InvalidExplicitCidV0)
+        ));
+        assert!(Cid::peek_header(&[0x02, 0x70, 0x12][..]).is_err());
+    }
+
+    #[test]
+    fn test_non_minimal_varints() {
+        let cid = Cid::new_v1(RAW, Code::Sha2_256.digest(b"foo"));
+        let bytes = cid.to_bytes();
+        assert_eq!(bytes[..3], [0x01, 0x55, 0x12]);
+        // Overlong encodings of the version, the codec and the multihash code.
+        for (index, overlong) in [(0, [0x81, 0x00]), (1, [0xd5, 0x00]), (2, [0x92, 0x00])] {
+            let mut bytes = bytes.clone();
+            bytes.splice(index..=index, overlong);
+            assert!(Cid::read_bytes(&bytes[..]).is_err());
+            assert!(Cid::try_from(&bytes[..]).is_err());
+        }
+        let mut bytes = bytes;
+        bytes.splice(1..=1, [0xd5, 0x80, 0x00]);
+        assert!(Cid::read_bytes(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_parts() {
+        let cid = Cid::from_str("QmTPcW343HGMdoxarwvHHoPhkbo5GfNYjnZkyW5DBtpvLe").unwrap();
+        let (version, codec, hash) = cid.into_parts();
+        assert_eq!((version, codec, &hash), (Version::V0, DAG_PB, cid.hash()));
+        assert_eq!(Cid::from_parts(version, codec, hash).unwrap(), cid);
+
+        // Swapping the codec of a CIDv0 needs a CIDv1.
+        assert!(matches!(
+            Cid::from_parts(version, RAW, hash),
+            Err(Error::InvalidCidV0Codec)
+        ));
+        let raw = Cid::from_parts(Version::V1, RAW, hash).unwrap();
+        assert_eq!(raw.into_parts(), (Version::V1, RAW, hash));
+    }
+
+    #[test]
+    fn test_builder() {
+        let hash = Code::Sha2_256.digest(b"foo");
+        let cid = CidBuilder::new().codec(RAW).hash(hash).build().unwrap();
+        assert_eq!(cid, Cid::new_v1(RAW, hash));
+        let v0 = CidBuilder::new()
+            .version(Version::V0)
+            .hash(hash)
+            .build()
+            .unwrap();
+        assert_eq!(v0, Cid::new_v0(hash).unwrap());
+
+        // Rewrite a single part of an existing CID.
+        let dag_pb = CidBuilder::from(cid).codec(DAG_PB).build().unwrap();
+        assert_eq!(dag_pb, Cid::new_v1(DAG_PB, hash));
+
+        assert!(matches!(
+            CidBuilder::<64>::new().codec(RAW).build(),
+            Err(Error::MissingBuilderField("hash"))
+        ));
+        assert!(matches!(
+            CidBuilder::new().hash(hash).build(),
+            Err(Error::MissingBuilderField("codec"))
+        ));
+        assert!(matches!(
+            CidBuilder::from(cid).version(Version::V0).build(),
+            Err(Error::InvalidCidV0Codec)
+        ));
+    }
+
+    #[test]
+    fn test_try_into_v0() {
+        let cid =
+            Cid::from_str("bafybeiclbsxcvqpfliqcejqz5ghpvw4r7vktjkyk3ruvjvdmam5azct2v4").unwrap();
+        let cid_v0 = cid.try_into_v0().unwrap();
+        assert_eq!(cid_v0.version(), Version::V0);
+        assert_eq!(
+            cid_v0.to_string(),
+            "QmTPcW343HGMdoxarwvHHoPhkbo5GfNYjnZkyW5DBtpvLe"
+        );
+        assert_eq!(cid_v0.try_into_v0().unwrap(), cid_v0);
+
+        let cid =
+            Cid::from_str("bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4").unwrap();
+        assert!(matches!(cid.try_into_v0(), Err(Error::InvalidCidV0Codec)));
+        let cid = Cid::new_v1(DAG_PB, Code::Sha2_512.digest(b"foo"));
+        assert!(matches!(
+            cid.try_into_v0(),
+            Err(Error::InvalidCidV0Multihash)
+        ));
+    }
 }
 
 #[cfg(all(test, not(feature = "std")))]
@@ -225,3 +567,63 @@ mod no_std_tests {
         assert!(Cid::read_bytes(&bad_cid[..]).is_err());
     }
 }
+
+#[cfg(feature = "test-vectors")]
+mod test_vectors {
+    use cid::test_vectors;
+
+    #[test]
+    fn shared_vectors() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/cid-vectors.json"
+        );
+        let vectors = test_vectors::load(path).unwrap();
+        assert!(!vectors.is_empty());
+        let failures = test_vectors::check_all(&vectors);
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+}
+
+#[cfg(all(feature = "derive", feature = "std"))]
+mod derive {
+    use std::convert::TryFrom;
+
+    use cid::{Cid, CidGeneric, CidWrapper};
+
+    const CID: &str = "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, CidWrapper)]
+    struct BlockCid(Cid);
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, CidWrapper)]
+    struct ManifestCid<const S: usize> {
+        cid: CidGeneric<S>,
+    }
+
+    #[test]
+    fn forwarded_impls() {
+        let cid: Cid = CID.parse().unwrap();
+        let block: BlockCid = CID.parse().unwrap();
+        assert_eq!(block, BlockCid::from(cid));
+        assert_eq!(block.to_string(), CID);
+        assert_eq!(block.codec(), 0x55);
+        assert_eq!(block.as_ref(), &cid);
+        assert_eq!(Cid::from(block), cid);
+        assert_eq!(BlockCid::try_from(&cid.to_bytes()[..]).unwrap(), block);
+        assert!("bafyinvalid".parse::<BlockCid>().is_err());
+
+        let manifest: ManifestCid<64> = CID.parse().unwrap();
+        assert_eq!(manifest.cid, cid);
+        assert_eq!(format!("{}", manifest), CID);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let block: BlockCid = CID.parse().unwrap();
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(json, serde_json::to_string(&*block).unwrap());
+        assert_eq!(serde_json::from_str::<BlockCid>(&json).unwrap(), block);
+    }
+}